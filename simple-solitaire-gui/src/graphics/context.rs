@@ -28,7 +28,10 @@ impl<'a> DrawContext<'a> {
     }
 
     pub fn card(&mut self, x: f32, y: f32, card: &cards::Card) {
-        self.card_raw(x, y, card.suit as u8, card.get_rank_value() - 1)
+        match card.facing {
+            cards::Facing::Up => self.card_raw(x, y, card.suit as u8, card.get_rank_value() - 1),
+            cards::Facing::Down => self.board_item(x, y, BoardItemStyle::Back),
+        }
     }
 
     fn card_raw(&mut self, x: f32, y: f32, card_s: u8, card_r: u8) {
@@ -43,6 +46,7 @@ impl<'a> DrawContext<'a> {
             BoardItemStyle::Back => {(2, 13)}
             BoardItemStyle::Empty => {(0, 15)}
             BoardItemStyle::Ace(suit) => {(suit as u8, 14)}
+            BoardItemStyle::None => return,
         };
 
         let instance = self.card_config.instance(x, y, sheet_x, sheet_y);
@@ -82,6 +82,8 @@ pub enum GameEvent {
     MouseMoved(glam::Vec2),
     MousePressed(glam::Vec2),
     MouseReleased(glam::Vec2),
+    MouseSecondaryPressed(glam::Vec2),
+    HintRequested,
 }
 
 
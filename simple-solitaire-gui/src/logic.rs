@@ -1,5 +1,4 @@
 use glam::Vec2;
-use rand::Rng;
 use crate::{GameEvent, SolitaireCursor};
 use crate::graphics::config::CardSizes;
 use crate::graphics::context::DrawContext;
@@ -11,31 +10,74 @@ pub(crate) struct SolitaireLogic {
     mouse_pos: Vec2,
     mouse_just_pressed: bool,
     mouse_just_released: bool,
+    mouse_just_secondary_pressed: bool,
     init: bool,
     game: games::Game,
+    hint: Option<cards::Move>,
     board_offset: Vec2,
+    #[cfg(not(target_arch = "wasm32"))]
+    last_tick: std::time::Instant,
 }
 
 impl SolitaireLogic {
     pub(crate) fn new() -> Self {
-        let entry = games::get_game_entries().first().expect("There should be at least one entry");
-        let mut game = (entry.creator)();
-        game.setup();
+        let mut game = load_last_game().unwrap_or_else(|| {
+            let entry = games::get_game_entries().first().expect("There should be at least one entry");
+            entry.create(games::DealMode::Random)
+        });
+        game.set_auto_play(games::AutoPlay::SafeOnly);
 
         Self {
             mouse_down: false,
             mouse_pos: Vec2::ZERO,
             mouse_just_pressed: false,
             mouse_just_released: false,
+            mouse_just_secondary_pressed: false,
             init: false,
             game,
+            hint: None,
             board_offset: Vec2::ZERO,
+            #[cfg(not(target_arch = "wasm32"))]
+            last_tick: std::time::Instant::now(),
+        }
+    }
+
+    /// Passes the time since the last event on to the game's clock.
+    fn tick(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let now = std::time::Instant::now();
+            self.game.add_elapsed(now - self.last_tick);
+            self.last_tick = now;
+        }
+    }
+
+    /// Saves the game so the next run picks it back up. Finished games aren't kept.
+    pub(crate) fn save_game(&mut self) {
+        self.tick();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(path) = save_path() else { return };
+            let result = match self.game.get_status().is_finished() {
+                true => std::fs::remove_file(&path).or_else(|e| match e.kind() {
+                    std::io::ErrorKind::NotFound => Ok(()),
+                    _ => Err(e),
+                }),
+                false => path.parent().map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::write(&path, save::save_game(&self.game))),
+            };
+
+            if let Err(e) = result {
+                log::warn!("Couldn't save the game to {}: {e}", path.display());
+            }
         }
     }
 
     fn process_event(&mut self, event: GameEvent) {
         self.mouse_just_released = false;
         self.mouse_just_pressed = false;
+        self.mouse_just_secondary_pressed = false;
 
         match event {
             GameEvent::MouseMoved(pos) => {
@@ -51,6 +93,13 @@ impl SolitaireLogic {
                 self.mouse_just_released = self.mouse_down;
                 self.mouse_down = false;
             }
+            GameEvent::MouseSecondaryPressed(pos) => {
+                self.mouse_pos = pos;
+                self.mouse_just_secondary_pressed = true;
+            }
+            GameEvent::HintRequested => {
+                self.hint = self.game.hint();
+            }
         }
     }
 
@@ -66,47 +115,177 @@ impl SolitaireLogic {
         }
 
         self.process_event(event);
+        self.tick();
+
+        if self.mouse_just_pressed || self.mouse_just_secondary_pressed {
+            self.hint = None;
+        }
+
+        if self.mouse_just_pressed {
+            if let Some(action_id) = self.action_item_at(self.mouse_pos, card_info) {
+                self.game.handle_event(games::GameEvent::ActionEvent(action_id));
+            } else if self.game.plays_by_picking() {
+                if let Some(card_loc) = self.card_at(self.mouse_pos, card_info) {
+                    self.game.handle_event(games::GameEvent::PickEvent(card_loc));
+                }
+            }
+        }
+
+        if self.mouse_just_secondary_pressed {
+            if let Some(card_loc) = self.card_at(self.mouse_pos, card_info) {
+                self.game.handle_event(games::GameEvent::SendEvent(card_loc));
+            }
+        }
 
         SolitaireCursor::Pointer
     }
 
+    fn action_item_at(&self, pos: Vec2, card_info: &CardSizes) -> Option<cards::ActionItemId> {
+        self.game.board_ref().action_item_iter()
+            .find(|item| is_over_item(pos, board_item_pos(&item.loc, card_info, &self.board_offset), card_info))
+            .map(|item| item.id())
+    }
+
+    /// The topmost card under `pos`, if any.
+    fn card_at(&self, pos: Vec2, card_info: &CardSizes) -> Option<cards::CardLocation> {
+        self.game.board_ref().pile_iter()
+            .flat_map(|pile| pile.card_iter_ex().map(move |(_, loc)| (pile, loc)))
+            .filter(|(pile, loc)| is_over_item(pos, card_pos(pile, loc.card_idx, card_info, &self.board_offset), card_info))
+            .last()
+            .map(|(_, loc)| loc)
+    }
+
     pub(crate) fn render(&self, draw: &mut DrawContext, card_info: &CardSizes) {
         let text = "Free Cell";
         let (text_width, _) = draw.get_text_size(text);
         draw.text("Free Cell", 0. - text_width / 2., 20.);
 
+        for action_item in self.game.board_ref().action_item_iter() {
+            draw.draw_action_item(action_item, card_info, &self.board_offset);
+        }
+
         for pile in self.game.board_ref().pile_iter() {
             draw.draw_pile(pile, card_info, &self.board_offset);
             for (card, loc) in pile.card_iter_ex() {
                 draw.draw_card(card, pile, loc, card_info, &self.board_offset);
             }
         }
+
+        for picked in self.game.picked() {
+            draw.draw_picked(self.game.board_ref(), *picked, card_info, &self.board_offset);
+        }
+
+        if let Some(hint) = self.hint {
+            draw.draw_hint(self.game.board_ref(), hint, card_info, &self.board_offset);
+        }
+    }
+}
+
+/// Where the game in progress is kept between runs.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_path() -> Option<std::path::PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("simple-solitaire").join("last-game.json"))
+}
+
+/// The game left unfinished last time, if there is one that still loads.
+fn load_last_game() -> Option<games::Game> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let path = save_path()?;
+        let text = std::fs::read_to_string(&path).ok()?;
+        match save::load_game(&text) {
+            Ok(game) => return Some(game),
+            Err(e) => log::warn!("Couldn't resume the last game from {}: {e}", path.display()),
+        }
     }
+
+    None
+}
+
+/// The upper left corner of whatever sits at `loc` on the board.
+fn board_item_pos(loc: &cards::BoardLocation, card_info: &CardSizes, board_offset: &Vec2) -> Vec2 {
+    let x = (loc.x as f32 * card_info.card_width()) + (loc.x as f32 * card_info.pile_padding_x()) + board_offset.x;
+    let y = (loc.y as f32 * card_info.card_height()) + (loc.y as f32 * card_info.pile_padding_y()) + board_offset.y;
+
+    Vec2::new(x, y)
+}
+
+/// The upper left corner of `pile`, moved off its board location by the pile's offset.
+fn pile_pos(pile: &cards::Pile, card_info: &CardSizes, board_offset: &Vec2) -> Vec2 {
+    let pos = board_item_pos(&pile.loc, card_info, board_offset);
+    let x = pile.offset.x * (card_info.card_width() + card_info.pile_padding_x());
+    let y = pile.offset.y * (card_info.card_height() + card_info.pile_padding_y());
+
+    pos + Vec2::new(x, y)
+}
+
+/// The upper left corner of the card at `card_idx` in `pile`.
+fn card_pos(pile: &cards::Pile, card_idx: usize, card_info: &CardSizes, board_offset: &Vec2) -> Vec2 {
+    let Vec2 { x: pile_x, y: pile_y } = pile_pos(pile, card_info, board_offset);
+
+    let card_x = pile_x + (card_idx as f32 * match pile.flow {
+        cards::PileFlow::Stack => 0.,
+        cards::PileFlow::Down => 0.,
+        cards::PileFlow::Right => card_info.card_offset_x(),
+    });
+
+    let card_y = pile_y + (card_idx as f32 * match pile.flow {
+        cards::PileFlow::Stack => 0.,
+        cards::PileFlow::Down => card_info.card_offset_y(),
+        cards::PileFlow::Right => 0.,
+    });
+
+    Vec2::new(card_x, card_y)
+}
+
+fn is_over_item(pos: Vec2, origin: Vec2, card_info: &CardSizes) -> bool {
+    pos.x >= origin.x && pos.x < origin.x + card_info.card_width()
+        && pos.y >= origin.y && pos.y < origin.y + card_info.card_height()
 }
 
 impl DrawContext<'_> {
+    fn draw_action_item(&mut self, action_item: &cards::ActionItem, card_info: &CardSizes, board_offset: &Vec2) {
+        let pos = board_item_pos(&action_item.loc, card_info, board_offset);
+
+        self.board_item(pos.x, pos.y, action_item.style)
+    }
+
     fn draw_pile(&mut self, pile: &cards::Pile, card_info: &CardSizes, board_offset: &Vec2) {
-        let pile_x = (pile.loc.x as f32 * card_info.card_width()) + (pile.loc.x as f32 * card_info.pile_padding_x()) + board_offset.x;
-        let pile_y = (pile.loc.y as f32 * card_info.card_height()) + (pile.loc.y as f32 * card_info.pile_padding_y()) + board_offset.y;
+        let pos = pile_pos(pile, card_info, board_offset);
 
-        self.board_item(pile_x, pile_y, pile.empty_style)
+        self.board_item(pos.x, pos.y, pile.empty_style)
     }
 
-    fn draw_card(&mut self, card: &cards::Card, pile: &cards::Pile, card_loc: cards::CardLocation, card_info: &CardSizes, board_offset: &Vec2) {
-        let pile_x = (pile.loc.x as f32 * card_info.card_width()) + (pile.loc.x as f32 * card_info.pile_padding_x()) + board_offset.x;
-        let pile_y = (pile.loc.y as f32 * card_info.card_height()) + (pile.loc.y as f32 * card_info.pile_padding_y()) + board_offset.y;
+    /// Lifts the hinted cards off their pile and shows the first of them again where it would land.
+    fn draw_hint(&mut self, board: &cards::Board, hint: cards::Move, card_info: &CardSizes, board_offset: &Vec2) {
+        let source = board.get_pile(hint.source.pile_id);
+        let target = board.get_pile(hint.target);
+        let lift = Vec2::new(0., card_info.card_offset_y() / 2.);
 
-        let card_x = pile_x + (card_loc.card_idx as f32 * match pile.flow {
-            cards::PileFlow::Stack => 0.,
-            cards::PileFlow::Down => 0.,
-            cards::PileFlow::Right => card_info.card_offset_x(),
-        });
+        for (card, loc) in source.card_iter_ex().skip(hint.source.card_idx) {
+            let pos = card_pos(source, loc.card_idx, card_info, board_offset) - lift;
+            self.card(pos.x, pos.y, card);
+        }
 
-        let card_y = pile_y + (card_loc.card_idx as f32 * match pile.flow {
-            cards::PileFlow::Stack => 0.,
-            cards::PileFlow::Down => card_info.card_offset_y(),
-            cards::PileFlow::Right => 0.,
-        });
+        if let Some((card, _)) = source.card_iter_ex().nth(hint.source.card_idx) {
+            let pos = card_pos(target, target.len(), card_info, board_offset);
+            self.card(pos.x, pos.y, card);
+        }
+    }
+
+    /// Lifts a picked card off its pile.
+    fn draw_picked(&mut self, board: &cards::Board, picked: cards::CardLocation, card_info: &CardSizes, board_offset: &Vec2) {
+        let pile = board.get_pile(picked.pile_id);
+        let lift = Vec2::new(0., card_info.card_offset_y() / 2.);
+
+        if let Some((card, _)) = pile.card_iter_ex().nth(picked.card_idx) {
+            let pos = card_pos(pile, picked.card_idx, card_info, board_offset) - lift;
+            self.card(pos.x, pos.y, card);
+        }
+    }
+
+    fn draw_card(&mut self, card: &cards::Card, pile: &cards::Pile, card_loc: cards::CardLocation, card_info: &CardSizes, board_offset: &Vec2) {
+        let Vec2 { x: card_x, y: card_y } = card_pos(pile, card_loc.card_idx, card_info, board_offset);
 
         self.card(card_x, card_y, card);
     }
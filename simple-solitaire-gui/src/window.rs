@@ -2,7 +2,7 @@ use crate::graphics::context::DrawContext;
 use crate::graphics::WgpuState;
 use std::sync::Arc;
 use winit::{
-    application::ApplicationHandler, dpi::LogicalSize, event::*, window::Window
+    application::ApplicationHandler, dpi::LogicalSize, event::*, keyboard::{KeyCode, PhysicalKey}, window::Window
 };
 
 pub struct Application {
@@ -76,6 +76,7 @@ impl<'a> ApplicationHandler<()> for Application {
                         CLOSE REQUESTED
                     */
                     WindowEvent::CloseRequested => {
+                        self.app_logic.save_game();
                         event_loop.exit();
                         None
                     },
@@ -92,7 +93,7 @@ impl<'a> ApplicationHandler<()> for Application {
                     },
                     WindowEvent::MouseInput {
                         state,
-                        button: MouseButton::Left | MouseButton::Right,
+                        button: MouseButton::Left,
                         ..
                     } => {
                         match state {
@@ -100,6 +101,19 @@ impl<'a> ApplicationHandler<()> for Application {
                             ElementState::Released => Some(crate::GameEvent::MouseReleased(window.mouse_pos)),
                         }
                     },
+                    WindowEvent::MouseInput {
+                        state: ElementState::Pressed,
+                        button: MouseButton::Right,
+                        ..
+                    } => Some(crate::GameEvent::MouseSecondaryPressed(window.mouse_pos)),
+                    WindowEvent::KeyboardInput {
+                        event: KeyEvent {
+                            state: ElementState::Pressed,
+                            physical_key: PhysicalKey::Code(KeyCode::KeyH),
+                            ..
+                        },
+                        ..
+                    } => Some(crate::GameEvent::HintRequested),
                     /*
                         HANDLE REDRAWING AND RESIZING
                     */
@@ -1,32 +1,45 @@
 use std::cmp::{max, PartialEq};
+use std::hash::{Hash, Hasher};
 use std::iter::Rev;
 use std::slice::Iter;
+use serde::{Deserialize, Serialize};
 use crate::board::pile_logic::{PileBuilder, PileLogic};
-use crate::cards::{Card, Suit};
+use crate::cards::{Card, Rank, Suit};
+use crate::journal::MoveStep;
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
 pub struct BoardLocation {
     pub x: u8,
     pub y: u8,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum BoardItemStyle {
     Back,
     Empty,
     Ace(Suit),
+    /// Nothing is drawn, for spots that are only there to hold cards.
+    None,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+/// How far a pile sits from its board location, in board squares, for piles laid partly over one another.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PileOffset {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct PileId(pub usize);
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub enum PileFlow {
     Stack,
     Down,
     Right,
 }
 
+#[derive(Clone, Debug)]
 pub struct Pile {
     pub(crate) id: PileId,
     pub loc: BoardLocation,
@@ -34,13 +47,20 @@ pub struct Pile {
     logic: PileLogic,
     pub flow: PileFlow,
     pub empty_style: BoardItemStyle,
+    pub offset: PileOffset,
+    /// The piles lying over this one. None of its cards can be picked up until they're all empty.
+    covered_by: Vec<PileId>,
+    /// The Zobrist hash of the cards, kept up to date as they come and go.
+    hash: u64,
+    /// The pile this one stands in for when telling positions apart, which is itself unless it's interchangeable.
+    slot: PileId,
 }
 impl Pile {
-    pub(crate) fn can_accept_card(&self, incoming: &Card) -> bool {
-        self.logic.can_accept(&self.cards, incoming)
+    pub fn id(&self) -> PileId {
+        self.id
     }
 
-    pub(crate) fn can_accept_cards(&self, incoming: &Vec<Card>) -> bool {
+    pub(crate) fn can_accept_cards(&self, incoming: &[Card]) -> bool {
         if let Some(first_card) = incoming.first() {
             return self.logic.can_accept(&self.cards, first_card);
         }
@@ -53,57 +73,123 @@ impl Pile {
     }
 
     pub(crate) fn take_from_card(&mut self, target_idx: usize) -> Vec<Card> {
+        for (depth, card) in self.cards.iter().enumerate().skip(target_idx) {
+            self.hash ^= placement_key(depth, card);
+        }
+
         self.cards.drain(target_idx..).collect()
     }
 
     pub(crate) fn add_cards(&mut self, incoming: &mut Vec<Card>) {
-        self.cards.append(incoming);
+        for card in incoming.drain(..) {
+            self.add_card(card);
+        }
     }
 
     pub(crate) fn add_card(&mut self, incoming: Card) {
+        self.hash ^= placement_key(self.cards.len(), &incoming);
         self.cards.push(incoming);
     }
 
-    pub fn card_iter(&self) -> Iter<Card> {
+    pub(crate) fn turn_over_top(&mut self) {
+        let depth = self.cards.len().saturating_sub(1);
+        if let Some(card) = self.cards.last_mut() {
+            self.hash ^= placement_key(depth, card);
+            card.turn_over();
+            self.hash ^= placement_key(depth, card);
+        }
+    }
+
+    pub(crate) fn should_flip_top(&self) -> bool {
+        self.logic.should_flip(&self.cards)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn top_card(&self) -> Option<&Card> {
+        self.cards.last()
+    }
+
+    pub fn card_iter(&self) -> Iter<'_, Card> {
         self.cards.iter()
     }
 
     pub fn card_iter_ex(&self) -> impl Iterator<Item = (&Card, CardLocation)> {
-        self.cards.iter().enumerate().map(|(i, c)| { return (c, CardLocation { pile_id: self.id, card_idx: i }) })
+        self.cards.iter().enumerate().map(|(i, c)| { (c, CardLocation { pile_id: self.id, card_idx: i }) })
     }
 
-    pub fn card_iter_rev(&self) -> Rev<Iter<Card>> {
+    pub fn card_iter_rev(&self) -> Rev<Iter<'_, Card>> {
         self.cards.iter().rev()
     }
 }
 
+/// The Zobrist key for a card lying `depth` cards up from the bottom of a pile. The keys are worked out rather
+/// than drawn at random, so a position hashes the same from one run to the next.
+fn placement_key(depth: usize, card: &Card) -> u64 {
+    let code = (card.suit as u64) << 4 | card.get_rank_value() as u64 | (!card.is_face_up() as u64) << 6;
+    mix(code | (depth as u64) << 8)
+}
+
+/// The SplitMix64 finaliser, which spreads every bit of its input over the whole output.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub struct CardLocation {
     pub pile_id: PileId,
     pub card_idx: usize,
 }
 
-pub(crate) struct ActionItemId(pub usize);
+/// Moving the card at `source`, along with every card on top of it, onto the `target` pile.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Move {
+    pub source: CardLocation,
+    pub target: PileId,
+}
+
+impl Move {
+    pub fn card_count(&self, board: &Board) -> usize {
+        board.get_pile(self.source.pile_id).len() - self.source.card_idx
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+pub struct ActionItemId(pub usize);
+
+/// A clickable spot on the board, such as the stock. Activating one sends a `GameEvent::ActionEvent` to the game.
+#[derive(Clone, Debug)]
+pub struct ActionItem {
+    pub(crate) id: ActionItemId,
+    pub loc: BoardLocation,
+    pub style: BoardItemStyle,
+}
 
-pub(crate) struct ActionItem {
-    id: ActionItemId,
-    loc: BoardLocation,
-    style: BoardItemStyle,
+impl ActionItem {
+    pub fn id(&self) -> ActionItemId {
+        self.id
+    }
 }
 
+/// The piles and action items a game is played on. Boards compare equal when their cards lie the same way, as
+/// `is_same_position` decides.
+#[derive(Default, Clone, Debug)]
 pub struct Board {
     piles: Vec<Pile>,
     action_items: Vec<ActionItem>,
+    redeals: usize,
 }
 
 impl Board {
-    pub fn default() -> Self {
-        Self {
-            // selected_pile: None,
-            piles: Vec::new(),
-            action_items: Vec::new(),
-        }
-    }
-
     pub(crate) fn get_next_pile_id(&self) -> PileId {
         self.piles.iter().map(|p| p.id).max().map(|id| PileId(id.0 + 1)).unwrap_or(PileId(0))
     }
@@ -124,10 +210,149 @@ impl Board {
         self.action_items.push(action_item);
     }
 
+    pub(crate) fn create_action_item(&mut self, loc: BoardLocation, style: BoardItemStyle) -> ActionItemId {
+        let id = self.action_items.iter().map(|a| a.id).max().map(|id| ActionItemId(id.0 + 1)).unwrap_or(ActionItemId(0));
+        self.add_action_item(ActionItem { id, loc, style });
+
+        id
+    }
+
+    /// Sets the rank that empty piles with `EmptyAcceptLogic::Base` take, once the deal has picked it.
+    pub(crate) fn set_base_rank(&mut self, pile_ids: &[PileId], rank: Rank) {
+        for id in pile_ids {
+            self.get_pile_mut(*id).logic.set_base_rank(rank);
+        }
+    }
+
+    /// Lays piles over another, as in Pyramid's triangle, so its cards can't be picked up until they're all empty.
+    pub(crate) fn cover(&mut self, pile_id: PileId, covering: &[PileId]) {
+        self.get_pile_mut(pile_id).covered_by = covering.to_vec();
+    }
+
+    fn is_uncovered(&self, pile: &Pile) -> bool {
+        pile.covered_by.iter().all(|id| self.get_pile(*id).is_empty())
+    }
+
+    /// Whether the card can be picked up, along with the cards on top of it, by the pile rules and with nothing
+    /// lying over its pile.
+    pub fn is_card_available(&self, location: CardLocation) -> bool {
+        let pile = self.get_pile(location.pile_id);
+        location.card_idx < pile.len() && self.is_uncovered(pile) && pile.is_card_available(location.card_idx)
+    }
+
+    /// Marks piles whose order makes no difference to the game, such as free cells, so positions that only differ
+    /// in which of them holds what count as the same.
+    pub(crate) fn make_interchangeable(&mut self, pile_ids: &[PileId]) {
+        let Some(slot) = pile_ids.iter().min().copied() else { return };
+        for id in pile_ids {
+            self.get_pile_mut(*id).slot = slot;
+        }
+    }
+
+    /// A hash of where every card lies and which way up it is, the same from one run to the next. Each pile keeps
+    /// its own part up to date as cards move, so this only has to combine one value per pile. Interchangeable
+    /// piles hash the same whichever of them holds what, and the redeal count is left out.
+    pub fn position_hash(&self) -> u64 {
+        self.piles.iter().fold(0, |hash, pile| hash.wrapping_add(mix(pile.hash ^ mix(pile.slot.0 as u64))))
+    }
+
+    /// Whether the cards lie the same way on both boards, letting interchangeable piles swap what they hold.
+    pub fn is_same_position(&self, other: &Board) -> bool {
+        if self.piles.len() != other.piles.len() || self.position_hash() != other.position_hash() { return false }
+
+        let mut unmatched: Vec<&Pile> = other.piles.iter().collect();
+        self.piles.iter().all(|pile| {
+            let found = unmatched.iter().position(|other| other.slot == pile.slot && other.cards == pile.cards);
+            found.map(|idx| unmatched.swap_remove(idx)).is_some()
+        })
+    }
+
+    /// How many times the waste has been turned back over into the stock.
+    pub fn redeal_count(&self) -> usize {
+        self.redeals
+    }
+
+    pub fn get_pile(&self, pile_id: PileId) -> &Pile {
+        self.piles.iter().find(|p| { p.id == pile_id }).expect("Pile ID is invalid")
+    }
+
     pub(crate) fn get_pile_mut(&mut self, pile_id: PileId) -> &mut Pile {
         self.piles.iter_mut().find(|p| { p.id == pile_id }).expect("Pile ID is invalid")
     }
 
+    pub(crate) fn apply_step(&mut self, step: &MoveStep) {
+        match *step {
+            MoveStep::Transfer { source, target, count } => {
+                let source = self.get_pile_mut(source);
+                let mut cards = source.take_from_card(source.len() - count);
+                self.get_pile_mut(target).add_cards(&mut cards);
+            }
+            MoveStep::Flip { pile_id } => self.get_pile_mut(pile_id).turn_over_top(),
+            MoveStep::Redeal => self.redeals += 1,
+        }
+    }
+
+    /// Whether the step could have been the last one made on the board, so it can be taken back.
+    pub(crate) fn can_revert_step(&self, step: &MoveStep) -> bool {
+        let pile_len = |id: PileId| self.piles.iter().find(|p| p.id == id).map(|p| p.len());
+
+        match *step {
+            MoveStep::Transfer { source, target, count } => {
+                pile_len(source).is_some() && pile_len(target).is_some_and(|len| len >= count) && source != target
+            }
+            MoveStep::Flip { pile_id } => pile_len(pile_id).is_some_and(|len| len > 0),
+            MoveStep::Redeal => self.redeals > 0,
+        }
+    }
+
+    pub(crate) fn revert_step(&mut self, step: &MoveStep) {
+        match *step {
+            MoveStep::Transfer { source, target, count } => self.apply_step(&MoveStep::Transfer { source: target, target: source, count }),
+            MoveStep::Flip { .. } => self.apply_step(step),
+            MoveStep::Redeal => self.redeals -= 1,
+        }
+    }
+
+    /// Turns face up every face-down top card the pile rules say should flip, returning the flips made. Cards on
+    /// covered piles wait until the piles over them are cleared.
+    pub(crate) fn auto_flip(&mut self) -> Vec<MoveStep> {
+        let flips: Vec<MoveStep> = self.piles.iter()
+            .filter(|p| p.should_flip_top() && self.is_uncovered(p))
+            .map(|p| MoveStep::Flip { pile_id: p.id })
+            .collect();
+
+        for step in flips.iter() {
+            self.apply_step(step);
+        }
+
+        flips
+    }
+
+    /// Whether the target pile would take the cards the move picks up. Games may still refuse it.
+    pub fn accepts_move(&self, mv: &Move) -> bool {
+        let source = self.get_pile(mv.source.pile_id);
+        mv.target != mv.source.pile_id
+            && self.is_card_available(mv.source)
+            && self.get_pile(mv.target).can_accept_cards(&source.cards[mv.source.card_idx..])
+    }
+
+    /// Every move the pile rules allow: each available card, with the cards on top of it, onto each pile that accepts them.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.legal_move_iter().collect()
+    }
+
+    pub(crate) fn legal_move_iter(&self) -> impl Iterator<Item = Move> + '_ {
+        self.piles.iter().filter(|source| self.is_uncovered(source)).flat_map(move |source| {
+            (0..source.cards.len())
+                .filter(|idx| source.is_card_available(*idx))
+                .flat_map(move |idx| {
+                    self.piles.iter()
+                        .filter(move |target| target.id != source.id && target.can_accept_cards(&source.cards[idx..]))
+                        .map(move |target| Move { source: CardLocation { pile_id: source.id, card_idx: idx }, target: target.id })
+                })
+        })
+    }
+
     pub fn max_board_pos(&self) -> BoardLocation {
         let mut x = 0;
         let mut y = 0;
@@ -137,98 +362,149 @@ impl Board {
             y = max(y, pile.loc.y);
         }
 
+        for action_item in self.action_items.iter() {
+            x = max(x, action_item.loc.x);
+            y = max(y, action_item.loc.y);
+        }
+
         BoardLocation { x, y }
     }
 
-    pub fn pile_iter(&self) -> Iter<Pile> {
+    pub fn pile_iter(&self) -> Iter<'_, Pile> {
         self.piles.iter()
     }
+
+    pub fn action_item_iter(&self) -> Iter<'_, ActionItem> {
+        self.action_items.iter()
+    }
+}
+
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_same_position(other)
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.position_hash());
+    }
 }
 
 pub mod pile_logic {
-    use crate::board::{BoardItemStyle, BoardLocation, Pile, PileFlow, PileId};
+    use crate::board::{BoardItemStyle, BoardLocation, Pile, PileFlow, PileId, PileOffset};
     use crate::cards;
 
-    #[derive(Copy, Clone)]
+    #[derive(Copy, Clone, Debug)]
     pub enum SuitOrdering {
         Any,
         Same,
-        SameColor,
         AlternatingColor,
     }
-    #[derive(Copy, Clone)]
+    impl SuitOrdering {
+        pub fn is_ordered(&self, top: &cards::Card, bottom: &cards::Card) -> bool {
+            match self {
+                SuitOrdering::Any => true,
+                SuitOrdering::Same => top.suit == bottom.suit,
+                SuitOrdering::AlternatingColor => top.get_color() != bottom.get_color(),
+            }
+        }
+    }
+    #[derive(Copy, Clone, Debug)]
     pub enum RankOrdering {
         Any,
-        Descending,
         Decrementing,
-        Increasing,
         Incrementing,
+        /// One rank lower, going from aces round to kings.
+        DecrementingWrapping,
+        /// One rank higher, going from kings round to aces.
+        IncrementingWrapping,
+        /// One rank higher or lower, as on the waste in Golf.
+        Adjacent,
+        /// One rank higher or lower, with kings and aces next to each other too.
+        AdjacentWrapping,
     }
-    #[derive(Copy, Clone)]
+    #[derive(Copy, Clone, Debug)]
     pub enum Availability {
         All,
         Ordered,
         Top,
+        None,
     }
-    #[derive(Copy, Clone)]
+    #[derive(Copy, Clone, Debug)]
     pub enum AcceptLogic {
         Any,
         None,
         Ordered,
         Count(usize),
     }
-    #[derive(Copy, Clone)]
+    #[derive(Copy, Clone, Debug)]
     pub enum EmptyAcceptLogic {
         Any,
         None,
         Only(cards::Rank),
+        /// Only the rank the game picks when it deals, such as Canfield's first foundation card. Nothing until
+        /// it's been picked.
+        Base,
+    }
+    #[derive(Copy, Clone, Debug)]
+    pub enum AutoFlip {
+        /// Face-down cards stay face down until something else turns them over.
+        Never,
+        /// A face-down card is turned face up as soon as it becomes the top card.
+        Top,
     }
-    #[derive(Copy, Clone)]
+    #[derive(Copy, Clone, Debug)]
     pub struct PileLogic {
         suit: SuitOrdering,
+        run_suit: SuitOrdering,
         rank: RankOrdering,
         availability: Availability,
         accept: AcceptLogic,
         empty_accept: EmptyAcceptLogic,
+        auto_flip: AutoFlip,
+        base_rank: Option<cards::Rank>,
     }
 
     impl PileLogic {
         pub fn are_suits_ordered(&self, top: &cards::Card, bottom: &cards::Card) -> bool {
-            match self.suit {
-                SuitOrdering::Any => true,
-                SuitOrdering::Same => top.suit == bottom.suit,
-                SuitOrdering::SameColor => top.get_color() == bottom.get_color(),
-                SuitOrdering::AlternatingColor => top.get_color() != bottom.get_color(),
-            }
+            self.suit.is_ordered(top, bottom)
         }
 
         pub fn are_ranks_ordered(&self, top: &cards::Card, bottom: &cards::Card) -> bool {
             match self.rank {
                 RankOrdering::Any => true,
-                RankOrdering::Descending => top.get_rank_value() > bottom.get_rank_value(),
                 RankOrdering::Decrementing => top.get_rank_value().saturating_sub(bottom.get_rank_value()) == 1,
-                RankOrdering::Increasing => top.get_rank_value() < bottom.get_rank_value(),
                 RankOrdering::Incrementing => bottom.get_rank_value().saturating_sub(top.get_rank_value()) == 1,
+                RankOrdering::DecrementingWrapping => (top.get_rank_value() + 13 - bottom.get_rank_value()) % 13 == 1,
+                RankOrdering::IncrementingWrapping => (bottom.get_rank_value() + 13 - top.get_rank_value()) % 13 == 1,
+                RankOrdering::Adjacent => top.get_rank_value().abs_diff(bottom.get_rank_value()) == 1,
+                RankOrdering::AdjacentWrapping => matches!(top.get_rank_value().abs_diff(bottom.get_rank_value()), 1 | 12),
             }
         }
 
         pub fn are_cards_ordered(&self, top: &cards::Card, bottom: &cards::Card) -> bool {
-            self.are_suits_ordered(&top, &bottom) && self.are_ranks_ordered(&top, &bottom)
+            self.are_suits_ordered(top, bottom) && self.are_ranks_ordered(top, bottom)
         }
 
-        pub fn is_sequence_ordered(&self, cards: &[cards::Card]) -> bool {
-            if cards.len() == 1 { return true }
-
+        /// Whether the cards can be picked up together, which for `Availability::Ordered` follows the run suit
+        /// ordering rather than the one cards are built with.
+        pub fn is_run_ordered(&self, cards: &[cards::Card]) -> bool {
             cards.windows(2).all(|w| {
-                self.are_cards_ordered(&w[0], &w[1])
+                self.run_suit.is_ordered(&w[0], &w[1]) && self.are_ranks_ordered(&w[0], &w[1])
             })
         }
 
         pub fn is_sequence_available(&self, cards: &[cards::Card]) -> bool {
+            if !cards.iter().all(|c| c.is_face_up()) { return false }
+
             match self.availability {
                 Availability::All => true,
                 Availability::Top => cards.len() == 1,
-                Availability::Ordered => self.is_sequence_ordered(cards),
+                Availability::Ordered => self.is_run_ordered(cards),
+                Availability::None => false,
             }
         }
 
@@ -238,6 +514,7 @@ pub mod pile_logic {
                     EmptyAcceptLogic::Any => true,
                     EmptyAcceptLogic::None => false,
                     EmptyAcceptLogic::Only(rank) => incoming.rank == *rank,
+                    EmptyAcceptLogic::Base => self.base_rank == Some(incoming.rank),
                 }
                 Some(card) => match self.accept {
                     AcceptLogic::Any => true,
@@ -247,21 +524,35 @@ pub mod pile_logic {
                 }
             }
         }
+
+        pub(crate) fn set_base_rank(&mut self, rank: cards::Rank) {
+            self.base_rank = Some(rank);
+        }
+
+        pub fn should_flip(&self, cards: &[cards::Card]) -> bool {
+            match self.auto_flip {
+                AutoFlip::Never => false,
+                AutoFlip::Top => cards.last().is_some_and(|c| !c.is_face_up()),
+            }
+        }
     }
 
     pub struct PileBuilder {
         suit: Option<SuitOrdering>,
+        run_suit: Option<SuitOrdering>,
         rank: Option<RankOrdering>,
         availability: Option<Availability>,
         accept: Option<AcceptLogic>,
         empty_accept: Option<EmptyAcceptLogic>,
+        auto_flip: Option<AutoFlip>,
         pile_flow: Option<PileFlow>,
         pile_style: Option<BoardItemStyle>,
+        offset: Option<PileOffset>,
     }
     impl PileBuilder {
         pub fn new() -> Self {
             Self {
-                suit: None, rank: None, availability: None, accept: None, empty_accept: None, pile_flow: None, pile_style: None
+                suit: None, run_suit: None, rank: None, availability: None, accept: None, empty_accept: None, auto_flip: None, pile_flow: None, pile_style: None, offset: None
             }
         }
 
@@ -270,6 +561,12 @@ pub mod pile_logic {
             self
         }
 
+        /// The suit ordering a run has to follow to be picked up, when it differs from the one cards are built with.
+        pub fn with_run_suit_ordering(&mut self, run_suit_ordering: SuitOrdering) -> &mut Self {
+            self.run_suit = Some(run_suit_ordering);
+            self
+        }
+
         pub fn with_rank_ordering(&mut self, rank_ordering: RankOrdering) -> &mut Self {
             self.rank = Some(rank_ordering);
             self
@@ -290,6 +587,11 @@ pub mod pile_logic {
             self
         }
 
+        pub fn with_auto_flip(&mut self, auto_flip: AutoFlip) -> &mut Self {
+            self.auto_flip = Some(auto_flip);
+            self
+        }
+
         pub fn with_pile_flow(&mut self, pile_flow: PileFlow) -> &mut Self {
             self.pile_flow = Some(pile_flow);
             self
@@ -300,13 +602,21 @@ pub mod pile_logic {
             self
         }
 
+        pub fn with_offset(&mut self, x: f32, y: f32) -> &mut Self {
+            self.offset = Some(PileOffset { x, y });
+            self
+        }
+
         pub fn build_logic(&self) -> PileLogic {
             PileLogic {
                 suit: self.suit.unwrap_or(SuitOrdering::Any),
+                run_suit: self.run_suit.or(self.suit).unwrap_or(SuitOrdering::Any),
                 rank: self.rank.unwrap_or(RankOrdering::Any),
                 availability: self.availability.unwrap_or(Availability::All),
                 accept: self.accept.unwrap_or(AcceptLogic::Any),
                 empty_accept: self.empty_accept.unwrap_or(EmptyAcceptLogic::Any),
+                auto_flip: self.auto_flip.unwrap_or(AutoFlip::Top),
+                base_rank: None,
             }
         }
 
@@ -320,7 +630,114 @@ pub mod pile_logic {
                 cards: Vec::new(),
                 flow: self.pile_flow.unwrap_or(PileFlow::Down),
                 empty_style: self.pile_style.unwrap_or(BoardItemStyle::Empty),
+                offset: self.offset.unwrap_or_default(),
+                covered_by: Vec::new(),
+                hash: 0,
+                slot: id,
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::pile_logic::{AcceptLogic, AutoFlip, EmptyAcceptLogic, RankOrdering};
+
+    fn two_piles(auto_flip: AutoFlip) -> (Board, PileId, PileId) {
+        let mut board = Board::default();
+        let source = board.create_pile(BoardLocation { x: 0, y: 0 }, |b| b.with_auto_flip(auto_flip));
+        let target = board.create_pile(BoardLocation { x: 1, y: 0 }, |b| b);
+
+        let pile = board.get_pile_mut(source);
+        pile.add_card(Card::new(Suit::Spades, Rank::King).turned_over());
+        pile.add_card(Card::new(Suit::Hearts, Rank::Queen));
+
+        (board, source, target)
+    }
+
+    #[test]
+    fn face_down_cards_are_not_available() {
+        let (board, source, _) = two_piles(AutoFlip::Top);
+        let pile = board.get_pile(source);
+
+        assert!(!pile.is_card_available(0));
+        assert!(pile.is_card_available(1));
+    }
+
+    #[test]
+    fn exposed_card_flips_and_reverts() {
+        let (mut board, source, target) = two_piles(AutoFlip::Top);
+        let transfer = MoveStep::Transfer { source, target, count: 1 };
+
+        board.apply_step(&transfer);
+        let flips = board.auto_flip();
+        assert_eq!(flips, vec![MoveStep::Flip { pile_id: source }]);
+        assert!(board.get_pile(source).top_card().unwrap().is_face_up());
+
+        for step in flips.iter().rev().chain([transfer].iter()) {
+            board.revert_step(step);
+        }
+        assert!(!board.get_pile(source).card_iter().next().unwrap().is_face_up());
+        assert_eq!(board.get_pile(source).len(), 2);
+    }
+
+    #[test]
+    fn never_flip_leaves_top_face_down() {
+        let (mut board, source, target) = two_piles(AutoFlip::Never);
+
+        board.apply_step(&MoveStep::Transfer { source, target, count: 1 });
+        assert!(board.auto_flip().is_empty());
+    }
+
+    #[test]
+    fn position_hash_follows_moves_and_ignores_interchangeable_order() {
+        let (mut board, source, target) = two_piles(AutoFlip::Top);
+        let cell = board.create_pile(BoardLocation { x: 2, y: 0 }, |b| b);
+        let before = board.clone();
+
+        let transfer = MoveStep::Transfer { source, target, count: 1 };
+        board.apply_step(&transfer);
+        let flips = board.auto_flip();
+        assert_ne!(board.position_hash(), before.position_hash());
+
+        for step in flips.iter().rev().chain([transfer].iter()) {
+            board.revert_step(step);
+        }
+        assert_eq!(board.position_hash(), before.position_hash());
+        assert_eq!(board, before);
+
+        // Swapping the queen between two piles only counts as the same position once they're interchangeable
+        let mut swapped = board.clone();
+        swapped.apply_step(&MoveStep::Transfer { source, target, count: 1 });
+        board.apply_step(&MoveStep::Transfer { source, target: cell, count: 1 });
+        assert_ne!(board, swapped);
+
+        board.make_interchangeable(&[target, cell]);
+        swapped.make_interchangeable(&[target, cell]);
+        assert_eq!(board.position_hash(), swapped.position_hash());
+        assert_eq!(board, swapped);
+    }
+
+    #[test]
+    fn wrapping_foundation_builds_round_from_its_base() {
+        let mut board = Board::default();
+        let foundation = board.create_pile(BoardLocation { x: 0, y: 0 }, |b| {
+            b.with_rank_ordering(RankOrdering::IncrementingWrapping)
+                .with_accept(AcceptLogic::Ordered)
+                .with_empty_accept(EmptyAcceptLogic::Base)
+        });
+        let queen = [Card::new(Suit::Hearts, Rank::Queen)];
+        assert!(!board.get_pile(foundation).can_accept_cards(&queen));
+
+        board.set_base_rank(&[foundation], Rank::Queen);
+        let pile = board.get_pile_mut(foundation);
+        assert!(pile.can_accept_cards(&queen));
+        assert!(!pile.can_accept_cards(&[Card::new(Suit::Hearts, Rank::Ace)]));
+
+        for rank in [Rank::Queen, Rank::King] {
+            pile.add_card(Card::new(Suit::Hearts, rank));
+        }
+        assert!(pile.can_accept_cards(&[Card::new(Suit::Hearts, Rank::Ace)]));
+        assert!(!pile.can_accept_cards(&[Card::new(Suit::Hearts, Rank::Queen)]));
+    }
+}
@@ -1,12 +1,17 @@
-use rand::prelude::{SliceRandom, thread_rng};
+use std::fmt;
+use std::str::FromStr;
+use rand::prelude::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Eq)]
-pub(crate) enum CardColor {
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum CardColor {
     Red,
     Black,
 }
 
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum Suit {
     Spades,
     Diamonds,
@@ -18,9 +23,18 @@ impl Suit {
     pub fn get_ordered() -> [Suit;4] {
         [Suit::Spades, Suit::Diamonds, Suit::Clubs, Suit::Hearts]
     }
+
+    pub fn get_color(&self) -> CardColor {
+        match self {
+            Suit::Hearts |
+            Suit::Diamonds => CardColor::Red,
+            Suit::Clubs |
+            Suit::Spades => CardColor::Black,
+        }
+    }
 }
 
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum Rank {
     Ace,
     Value(u8),
@@ -29,19 +43,55 @@ pub enum Rank {
     King,
 }
 
+impl Rank {
+    pub fn from_value(value: u8) -> Option<Rank> {
+        match value {
+            1 => Some(Rank::Ace),
+            2..=10 => Some(Rank::Value(value)),
+            11 => Some(Rank::Jack),
+            12 => Some(Rank::Queen),
+            13 => Some(Rank::King),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Facing {
+    Up,
+    Down,
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize)]
 pub struct Card {
     pub suit: Suit,
     pub rank: Rank,
+    pub facing: Facing,
 }
 
 impl Card {
+    pub fn new(suit: Suit, rank: Rank) -> Self {
+        Self { suit, rank, facing: Facing::Up }
+    }
+
+    pub fn is_face_up(&self) -> bool {
+        self.facing == Facing::Up
+    }
+
+    pub fn turned_over(mut self) -> Self {
+        self.turn_over();
+        self
+    }
+
+    pub fn turn_over(&mut self) {
+        self.facing = match self.facing {
+            Facing::Up => Facing::Down,
+            Facing::Down => Facing::Up,
+        };
+    }
+
     pub fn get_color(&self) -> CardColor {
-        match self.suit {
-            Suit::Hearts |
-            Suit::Diamonds => CardColor::Red,
-            Suit::Clubs |
-            Suit::Spades => CardColor::Black,
-        }
+        self.suit.get_color()
     }
 
     pub fn get_rank_value(&self) -> u8 {
@@ -55,6 +105,112 @@ impl Card {
     }
 }
 
+/// Text that isn't a card, rank or suit in the usual notation.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseCardError {
+    text: String,
+}
+
+impl ParseCardError {
+    fn new(text: &str) -> Self {
+        Self { text: text.to_string() }
+    }
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" isn't a card in the usual notation", self.text)
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// The suit's initial, or with `{:#}` its symbol.
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (letter, symbol) = match self {
+            Suit::Spades => ('S', '♠'),
+            Suit::Diamonds => ('D', '♦'),
+            Suit::Clubs => ('C', '♣'),
+            Suit::Hearts => ('H', '♥'),
+        };
+
+        write!(f, "{}", if f.alternate() { symbol } else { letter })
+    }
+}
+
+/// Reads a suit's initial in either case, or its symbol.
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "S" | "s" | "♠" | "♤" => Ok(Suit::Spades),
+            "D" | "d" | "♦" | "♢" => Ok(Suit::Diamonds),
+            "C" | "c" | "♣" | "♧" => Ok(Suit::Clubs),
+            "H" | "h" | "♥" | "♡" => Ok(Suit::Hearts),
+            _ => Err(ParseCardError::new(s)),
+        }
+    }
+}
+
+/// One character per rank, with "T" for ten.
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rank::Ace => write!(f, "A"),
+            Rank::Value(10) => write!(f, "T"),
+            Rank::Value(value) => write!(f, "{value}"),
+            Rank::Jack => write!(f, "J"),
+            Rank::Queen => write!(f, "Q"),
+            Rank::King => write!(f, "K"),
+        }
+    }
+}
+
+/// Reads the rank's character in either case, and "10" as well as "T".
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = match s.to_ascii_uppercase().as_str() {
+            "A" => 1,
+            "T" | "10" => 10,
+            "J" => 11,
+            "Q" => 12,
+            "K" => 13,
+            digit => digit.parse().ok().filter(|value| (2..=9).contains(value)).ok_or(ParseCardError::new(s))?,
+        };
+
+        Ok(Rank::from_value(value).expect("Only values from one to thirteen are matched"))
+    }
+}
+
+/// The rank followed by the suit, such as "TD", or "T♦" with `{:#}`. Which way up the card is isn't shown.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.alternate() {
+            true => write!(f, "{}{:#}", self.rank, self.suit),
+            false => write!(f, "{}{}", self.rank, self.suit),
+        }
+    }
+}
+
+/// Reads a rank followed by a suit, such as "AS", "9c" or "10♥". The card comes out face up.
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s.char_indices().last().map(|(idx, _)| idx).filter(|idx| *idx > 0).ok_or(ParseCardError::new(s))?;
+        let (rank, suit) = s.split_at(split);
+
+        match (rank.parse(), suit.parse()) {
+            (Ok(rank), Ok(suit)) => Ok(Card::new(suit, rank)),
+            _ => Err(ParseCardError::new(s)),
+        }
+    }
+}
+
 pub(crate) struct Deck {
     cards: Vec<Card>
 }
@@ -69,25 +225,107 @@ impl Deck {
     }
 
     pub(crate) fn add_deck(&mut self) {
-        for suit in [Suit::Spades, Suit::Diamonds, Suit::Clubs, Suit::Hearts] {
-            for rank_value in 1..=13 {
-                match rank_value {
-                    1 => self.cards.push(Card { suit, rank: Rank::Ace }),
-                    2..=10 => self.cards.push(Card { suit, rank: Rank::Value(rank_value) }),
-                    11 => self.cards.push(Card { suit, rank: Rank::Jack }),
-                    12 => self.cards.push(Card { suit, rank: Rank::Queen }),
-                    13 => self.cards.push(Card { suit, rank: Rank::King }),
-                    _ => panic!("Somehow out of bounds")
-                }
+        for suit in Suit::get_ordered() {
+            self.add_suit(suit);
+        }
+    }
+
+    /// Adds the thirteen cards of a single suit.
+    pub(crate) fn add_suit(&mut self, suit: Suit) {
+        for rank_value in 1..=13 {
+            let rank = Rank::from_value(rank_value).expect("Somehow out of bounds");
+            self.cards.push(Card::new(suit, rank));
+        }
+    }
+
+    /// As many cards as `decks` full decks, but only in the given suits. Each suit is repeated in place of the
+    /// missing ones, so one suit gives every card in that suit and all four suits give ordinary decks.
+    pub(crate) fn suited_decks(decks: usize, suits: &[Suit]) -> Self {
+        let mut deck = Self::default();
+        for _ in 0..decks {
+            for i in 0..4 {
+                deck.add_suit(suits[i % suits.len()]);
             }
         }
+
+        deck
     }
 
     pub(crate) fn deal_card(&mut self) -> Option<Card> {
         self.cards.pop()
     }
 
-    pub(crate) fn shuffle(&mut self) {
-        self.cards.shuffle(&mut thread_rng());
+    /// Orders a single deck the way the Windows FreeCell dealt `deal_number`, so that `deal_card`
+    /// hands the cards out in the same order it did.
+    pub(crate) fn shuffle_microsoft(&mut self, deal_number: u32) {
+        assert_eq!(self.cards.len(), 52, "Microsoft deals are only defined for a single deck");
+
+        // Microsoft numbers the cards rank first, with suits ordered clubs, diamonds, hearts, spades
+        let suit_idx = |suit: Suit| match suit {
+            Suit::Clubs => 0,
+            Suit::Diamonds => 1,
+            Suit::Hearts => 2,
+            Suit::Spades => 3,
+        };
+        self.cards.sort_by_key(|c| (c.get_rank_value(), suit_idx(c.suit)));
+
+        let mut rng = MicrosoftRng::new(deal_number);
+        let mut dealt = Vec::with_capacity(self.cards.len());
+        while !self.cards.is_empty() {
+            let idx = rng.next_value() as usize % self.cards.len();
+            dealt.push(self.cards.swap_remove(idx));
+        }
+
+        dealt.reverse();
+        self.cards = dealt;
+    }
+
+    /// Shuffles the deck so that the same seed always gives the same order, on every platform.
+    pub(crate) fn shuffle(&mut self, seed: u64) {
+        self.cards.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+    }
+}
+
+/// The C runtime `rand()` the Windows FreeCell dealt with. Deals past 2^31 follow the
+/// FreeCell Pro extension, which sets the top bit of every value.
+struct MicrosoftRng {
+    state: u32,
+    high_bit: u32,
+}
+
+impl MicrosoftRng {
+    fn new(deal_number: u32) -> Self {
+        Self {
+            state: deal_number,
+            high_bit: if deal_number >= 0x8000_0000 { 0x8000 } else { 0 },
+        }
+    }
+
+    fn next_value(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(214013).wrapping_add(2531011);
+        ((self.state >> 16) & 0x7fff) | self.high_bit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cards_read_and_write_the_usual_notation() {
+        for (text, suit, value) in [("AS", Suit::Spades, 1), ("TD", Suit::Diamonds, 10), ("QH", Suit::Hearts, 12)] {
+            let card: Card = text.parse().unwrap();
+            assert_eq!(card, Card::new(suit, Rank::from_value(value).unwrap()));
+            assert_eq!(card.to_string(), text);
+        }
+
+        assert_eq!("9c".parse::<Card>().unwrap().to_string(), "9C");
+        assert_eq!("10♥".parse::<Card>().unwrap().to_string(), "TH");
+        assert_eq!(format!("{:#}", Card::new(Suit::Spades, Rank::King)), "K♠");
+        assert_eq!(format!("{:#}", "7♣".parse::<Card>().unwrap()), "7♣");
+
+        for text in ["", "S", "1S", "11H", "AX", "ASS"] {
+            assert_eq!(text.parse::<Card>(), Err(ParseCardError::new(text)));
+        }
     }
-}
\ No newline at end of file
+}
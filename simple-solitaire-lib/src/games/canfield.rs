@@ -0,0 +1,276 @@
+use crate::board::{ActionItemId, Board, BoardItemStyle, BoardLocation, PileFlow, PileId};
+use crate::board::pile_logic::{AcceptLogic, Availability, AutoFlip, EmptyAcceptLogic, RankOrdering, SuitOrdering};
+use crate::cards::Deck;
+use crate::games::{deal_from_stock, GameLogic, GameStatus};
+use crate::journal::MoveStep;
+
+/// Canfield, also known as Demon: a 13-card reserve with only its top card face up, four tableau piles built down
+/// in alternating colours, and foundations that start from whatever rank the first card dealt to them has,
+/// building up in suit and going round from kings to aces. A tableau pile that empties is filled straight from the
+/// reserve, or by the player once the reserve is gone. The stock deals three cards at a time.
+pub struct Canfield {
+    draw_count: usize,
+    redeal_limit: Option<usize>,
+    stock_id: PileId,
+    waste_id: PileId,
+    reserve_id: PileId,
+    foundation_ids: Vec<PileId>,
+    tableau_ids: Vec<PileId>,
+    stock_action: ActionItemId,
+}
+
+impl Canfield {
+    /// Limits how many times the waste can be turned back into the stock. Redeals are unlimited by default.
+    pub fn with_redeal_limit(mut self, redeal_limit: usize) -> Self {
+        self.redeal_limit = Some(redeal_limit);
+        self
+    }
+}
+
+impl Default for Canfield {
+    fn default() -> Self {
+        Self {
+            draw_count: 3,
+            redeal_limit: None,
+            stock_id: PileId(0),
+            waste_id: PileId(0),
+            reserve_id: PileId(0),
+            foundation_ids: Vec::new(),
+            tableau_ids: Vec::new(),
+            stock_action: ActionItemId(0),
+        }
+    }
+}
+
+impl GameLogic for Canfield {
+    fn setup(&mut self, board: &mut Board, seed: u64) {
+        self.foundation_ids.clear();
+        self.tableau_ids.clear();
+
+        // Stock
+        let loc = BoardLocation { x: 0, y: 0 };
+        self.stock_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::None)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_auto_flip(AutoFlip::Never)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+        self.stock_action = board.create_action_item(loc, BoardItemStyle::Empty);
+
+        // Waste
+        let loc = BoardLocation { x: 1, y: 0 };
+        self.waste_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::Top)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+
+        // Foundations
+        for i in 0..4 {
+            let loc = BoardLocation { x: i + 3, y: 0 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_empty_accept(EmptyAcceptLogic::Base)
+                    .with_suit_ordering(SuitOrdering::Same)
+                    .with_rank_ordering(RankOrdering::IncrementingWrapping)
+                    .with_accept(AcceptLogic::Ordered)
+                    .with_pile_style(BoardItemStyle::Empty)
+                    .with_pile_flow(PileFlow::Stack)
+                    .with_availability(Availability::Top)
+            });
+
+            self.foundation_ids.push(id);
+        }
+        board.make_interchangeable(&self.foundation_ids);
+
+        // Reserve
+        let loc = BoardLocation { x: 0, y: 1 };
+        self.reserve_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::Top)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+
+        // Tableau
+        for i in 0..4 {
+            let loc = BoardLocation { x: i + 3, y: 1 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_availability(Availability::Ordered)
+                    .with_suit_ordering(SuitOrdering::AlternatingColor)
+                    .with_rank_ordering(RankOrdering::DecrementingWrapping)
+                    .with_empty_accept(EmptyAcceptLogic::Any)
+                    .with_pile_style(BoardItemStyle::Empty)
+                    .with_pile_flow(PileFlow::Down)
+                    .with_accept(AcceptLogic::Ordered)
+            });
+
+            self.tableau_ids.push(id);
+        }
+
+        let mut deck = Deck::single_deck();
+        deck.shuffle(seed);
+
+        for i in 0..13 {
+            let card = deck.deal_card().expect("A single deck covers the reserve");
+            let card = if i == 12 { card } else { card.turned_over() };
+            board.get_pile_mut(self.reserve_id).add_card(card);
+        }
+
+        // The first foundation card sets the rank every foundation starts from
+        let card = deck.deal_card().expect("A single deck has a card for the foundations");
+        board.set_base_rank(&self.foundation_ids, card.rank);
+        board.get_pile_mut(self.foundation_ids[0]).add_card(card);
+
+        for id in &self.tableau_ids {
+            let card = deck.deal_card().expect("A single deck covers the tableau");
+            board.get_pile_mut(*id).add_card(card);
+        }
+
+        while let Some(card) = deck.deal_card() {
+            board.get_pile_mut(self.stock_id).add_card(card.turned_over());
+        }
+    }
+
+    fn foundation_ids(&self) -> &[PileId] {
+        &self.foundation_ids
+    }
+
+    fn tableau_ids(&self) -> &[PileId] {
+        &self.tableau_ids
+    }
+
+    fn get_status(&self, board: &Board) -> GameStatus {
+        if self.foundation_ids.iter().all(|id| board.get_pile(*id).len() == 13) {
+            return GameStatus::Won;
+        }
+
+        match self.legal_moves(board).is_empty() && self.on_action(board, self.stock_action).is_empty() {
+            true => GameStatus::Lost,
+            false => GameStatus::Ongoing,
+        }
+    }
+
+    fn on_action(&self, board: &Board, action: ActionItemId) -> Vec<MoveStep> {
+        if action != self.stock_action { return Vec::new() }
+
+        deal_from_stock(board, self.stock_id, self.waste_id, self.draw_count, self.redeal_limit)
+    }
+
+    /// Fills an empty tableau pile from the reserve while it has cards.
+    fn auto_move(&self, board: &Board) -> Option<MoveStep> {
+        if board.get_pile(self.reserve_id).is_empty() { return None }
+
+        self.tableau_ids.iter()
+            .find(|id| board.get_pile(**id).is_empty())
+            .map(|id| MoveStep::Transfer { source: self.reserve_id, target: *id, count: 1 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::CardLocation;
+    use crate::cards::{Card, Rank, Suit};
+    use crate::games::{Game, GameEvent};
+
+    // Pile ids are handed out in creation order: stock, waste, foundations, reserve, then the tableau
+    const WASTE: PileId = PileId(1);
+    const FOUNDATIONS: [PileId; 4] = [PileId(2), PileId(3), PileId(4), PileId(5)];
+    const RESERVE: PileId = PileId(6);
+    const TABLEAU: [PileId; 4] = [PileId(7), PileId(8), PileId(9), PileId(10)];
+
+    fn canfield_game(seed: u64) -> Game {
+        let mut game = Game::create_with_logic(Box::new(Canfield::default()));
+        game.setup_with_seed(seed);
+        game
+    }
+
+    #[test]
+    fn deal_layout() {
+        let game = canfield_game(7);
+        let board = game.board_ref();
+
+        let reserve = board.get_pile(RESERVE);
+        assert_eq!(reserve.len(), 13);
+        assert_eq!(reserve.card_iter().filter(|card| card.is_face_up()).count(), 1);
+        assert!(reserve.top_card().unwrap().is_face_up());
+
+        assert!(TABLEAU.iter().all(|id| board.get_pile(*id).len() == 1));
+        assert_eq!(board.get_pile(PileId(0)).len(), 52 - 13 - 1 - 4);
+
+        // Every foundation starts from the rank of the first card dealt
+        let base = board.get_pile(FOUNDATIONS[0]).top_card().unwrap().rank;
+        let empty = board.get_pile(FOUNDATIONS[1]);
+        assert!(empty.can_accept_cards(&[Card::new(Suit::Hearts, base)]));
+        assert!(FOUNDATIONS[1..].iter().all(|id| board.get_pile(*id).is_empty()));
+    }
+
+    #[test]
+    fn foundations_go_round_from_kings_to_aces() {
+        // Whatever rank the deal picked, these foundations start from kings
+        let mut game = Game::arranged(Canfield::default(), 7, |logic, board| {
+            board.set_base_rank(&logic.foundation_ids, Rank::King);
+        });
+        game.set_position("
+            Pile:
+            Pile: AS AH
+            Foundation: KH
+            Foundation:
+            Foundation:
+            Foundation:
+            Pile: [2C] 3D
+            : 9H
+            : 8S
+            : 7D
+            : 6C
+        ").unwrap();
+
+        game.handle_event(GameEvent::SendEvent(CardLocation { pile_id: WASTE, card_idx: 1 }));
+        assert_eq!(game.board_ref().get_pile(FOUNDATIONS[0]).len(), 2);
+
+        // An ace doesn't start a foundation of its own
+        game.handle_event(GameEvent::SendEvent(CardLocation { pile_id: WASTE, card_idx: 0 }));
+        assert!(FOUNDATIONS[1..].iter().all(|id| game.board_ref().get_pile(*id).is_empty()));
+    }
+
+    #[test]
+    fn reserve_fills_empty_tableau_piles() {
+        let mut game = canfield_game(7);
+        game.set_position("
+            Pile: [2H]
+            Pile:
+            Foundation:
+            Foundation:
+            Foundation:
+            Foundation:
+            Pile: [4D] 5S
+            : 9H
+            : TS
+            : 2D
+            : 3C
+        ").unwrap();
+
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: TABLEAU[0], card_idx: 0 }));
+        game.handle_event(GameEvent::DropEvent(Some(TABLEAU[1])));
+
+        let board = game.board_ref();
+        assert_eq!(board.get_pile(TABLEAU[1]).len(), 2);
+        assert_eq!(board.get_pile(TABLEAU[0]).top_card(), Some(&Card::new(Suit::Spades, Rank::Value(5))));
+        assert!(board.get_pile(RESERVE).top_card().unwrap().is_face_up());
+
+        assert!(game.undo());
+        assert_eq!(game.board_ref().get_pile(RESERVE).len(), 2);
+        assert!(!game.board_ref().get_pile(RESERVE).card_iter().next().unwrap().is_face_up());
+        assert_eq!(game.board_ref().get_pile(TABLEAU[0]).len(), 1);
+    }
+}
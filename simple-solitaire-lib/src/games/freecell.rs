@@ -0,0 +1,622 @@
+use rand::Rng;
+use crate::board::{PileId, Board, PileFlow, BoardLocation, BoardItemStyle, Move};
+use crate::board::pile_logic::{AcceptLogic, Availability, EmptyAcceptLogic, RankOrdering, SuitOrdering};
+use crate::cards::{Deck, Rank, Suit};
+use crate::games::{Game, GameLogic, GameStatus};
+
+#[derive(Default)]
+pub struct FreeCell {
+    cell_ids: Vec<PileId>,
+    foundation_ids: Vec<PileId>,
+    tableau_ids: Vec<PileId>,
+    microsoft_deals: bool,
+}
+
+impl FreeCell {
+    /// The highest deal number the later Windows versions offered.
+    pub const MICROSOFT_DEAL_COUNT: u32 = 1_000_000;
+
+    /// The name games dealt the Windows way go by, in the game entries and in saves.
+    pub const MICROSOFT_VARIANT: &'static str = "FreeCell (Microsoft)";
+
+    /// FreeCell dealt the same way as the Windows version, with its deal numbers as seeds.
+    pub fn microsoft() -> Self {
+        FreeCell { microsoft_deals: true, ..Default::default() }
+    }
+
+    /// A game laid out the same as deal `deal_number` of the Windows FreeCell. The deal number is the game's seed,
+    /// and dealing a new game afterwards picks another Microsoft deal.
+    pub fn microsoft_deal(deal_number: u32) -> Game {
+        let mut game = Game::create_with_logic(Box::new(Self::microsoft())).with_variant(Self::MICROSOFT_VARIANT);
+        game.setup_with_seed(deal_number as u64);
+
+        game
+    }
+
+    /// How many cards can move onto `target` at once, shuffling through the free cells and empty columns. An
+    /// empty target column can't also serve as a stopover, so it doesn't count.
+    fn supermove_limit(&self, board: &Board, target: PileId) -> usize {
+        let free_cells = self.cell_ids.iter().filter(|id| board.get_pile(**id).is_empty()).count();
+        let empty_columns = self.tableau_ids.iter()
+            .filter(|id| **id != target && board.get_pile(**id).is_empty())
+            .count();
+
+        (free_cells + 1) << empty_columns
+    }
+}
+impl GameLogic for FreeCell {
+    fn setup(&mut self, board: &mut Board, seed: u64) {
+        self.cell_ids.clear();
+        self.foundation_ids.clear();
+        self.tableau_ids.clear();
+
+        // Cells
+        for i in 0..4 {
+            let loc = BoardLocation { x: i, y: 0 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_pile_flow(PileFlow::Stack)
+                    .with_accept(AcceptLogic::Count(1))
+                    .with_pile_style(BoardItemStyle::Empty)
+            });
+
+            self.cell_ids.push(id);
+        }
+        board.make_interchangeable(&self.cell_ids);
+
+        // Foundations
+        for i in 0..4 {
+            let suit = Suit::get_ordered()[i as usize];
+            let loc = BoardLocation { x: i + 4, y: 0 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_empty_accept(EmptyAcceptLogic::Only(Rank::Ace))
+                    .with_suit_ordering(SuitOrdering::Same)
+                    .with_rank_ordering(RankOrdering::Incrementing)
+                    .with_accept(AcceptLogic::Ordered)
+                    .with_pile_style(BoardItemStyle::Ace(suit))
+                    .with_pile_flow(PileFlow::Stack)
+                    .with_availability(Availability::Top)
+            });
+
+            self.foundation_ids.push(id);
+        }
+        board.make_interchangeable(&self.foundation_ids);
+
+        // Tableau
+        for i in 0..8 {
+            let loc = BoardLocation { x: i, y: 1 };
+            let id = board.create_pile(loc, |builder| {
+               builder
+                   .with_availability(Availability::Ordered)
+                   .with_suit_ordering(SuitOrdering::AlternatingColor)
+                   .with_rank_ordering(RankOrdering::Decrementing)
+                   .with_empty_accept(EmptyAcceptLogic::Any)
+                   .with_pile_style(BoardItemStyle::Empty)
+                   .with_pile_flow(PileFlow::Down)
+                   .with_accept(AcceptLogic::Ordered)
+            });
+
+            self.tableau_ids.push(id);
+        }
+
+        let mut deck = Deck::single_deck();
+        match self.microsoft_deals {
+            true => deck.shuffle_microsoft(seed as u32),
+            false => deck.shuffle(seed),
+        }
+
+        let mut i = 0;
+        while let Some(card) = deck.deal_card() {
+            let pile_id = self.tableau_ids[i % 8];
+            board.get_pile_mut(pile_id).add_card(card);
+
+            i += 1;
+        }
+    }
+
+    fn random_seed(&self) -> u64 {
+        match self.microsoft_deals {
+            true => rand::thread_rng().gen_range(1..=Self::MICROSOFT_DEAL_COUNT) as u64,
+            false => rand::thread_rng().gen(),
+        }
+    }
+
+    /// Microsoft deal numbers are 32 bits, so larger seeds wrap round to one of them rather than being refused.
+    fn deal_seed(&self, seed: u64) -> u64 {
+        match self.microsoft_deals {
+            true => seed as u32 as u64,
+            false => seed,
+        }
+    }
+
+    fn is_move_allowed(&self, board: &Board, mv: &Move) -> bool {
+        let count = mv.card_count(board);
+        match self.tableau_ids.contains(&mv.target) {
+            true => count <= self.supermove_limit(board, mv.target),
+            false => count == 1,
+        }
+    }
+
+    fn foundation_ids(&self) -> &[PileId] {
+        &self.foundation_ids
+    }
+
+    fn tableau_ids(&self) -> &[PileId] {
+        &self.tableau_ids
+    }
+
+    fn cell_ids(&self) -> &[PileId] {
+        &self.cell_ids
+    }
+
+    fn get_status(&self, board: &Board) -> GameStatus {
+        let foundations_complete = self.foundation_ids.iter().all(|id| {
+            board.get_pile(*id).top_card().is_some_and(|card| card.rank == Rank::King)
+        });
+
+        if foundations_complete {
+            return GameStatus::Won;
+        }
+
+        match self.legal_moves(board).is_empty() {
+            true => GameStatus::Lost,
+            false => GameStatus::Ongoing,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use super::*;
+    use crate::board::{CardLocation, Move};
+    use crate::cards::Card;
+    use crate::games::{AutoPlay, GameEvent, ParsePositionError, StuckReason};
+
+    // Pile ids are handed out in creation order: the cells, the foundations, then the columns
+    const CELLS: [PileId; 4] = [PileId(0), PileId(1), PileId(2), PileId(3)];
+    const FOUNDATIONS: [PileId; 4] = [PileId(4), PileId(5), PileId(6), PileId(7)];
+    const COLUMNS: [PileId; 8] = [PileId(8), PileId(9), PileId(10), PileId(11), PileId(12), PileId(13), PileId(14), PileId(15)];
+
+    /// A game with the columns cleared, leaving `arrange` to set the cards out.
+    fn empty_freecell(arrange: impl FnOnce(&FreeCell, &mut Board)) -> Game {
+        Game::arranged(FreeCell::default(), 1, |logic, board| {
+            for id in logic.tableau_ids.iter() {
+                board.get_pile_mut(*id).take_from_card(0);
+            }
+
+            arrange(logic, board);
+        })
+    }
+
+    fn fill_foundations(logic: &FreeCell, board: &mut Board) {
+        let mut deck = Deck::single_deck();
+        let mut cards = Vec::new();
+        while let Some(card) = deck.deal_card() {
+            cards.push(card);
+        }
+
+        for card in cards.into_iter().rev() {
+            board.get_pile_mut(logic.foundation_ids[card.suit as usize]).add_card(card);
+        }
+    }
+
+    #[test]
+    fn fresh_deal_is_ongoing() {
+        let game = Game::arranged(FreeCell::default(), 1, |_, _| {});
+
+        assert_eq!(game.get_status(), GameStatus::Ongoing);
+    }
+
+    #[test]
+    fn full_foundations_are_won() {
+        let game = empty_freecell(fill_foundations);
+
+        assert_eq!(game.get_status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn no_moves_is_lost() {
+        let game = empty_freecell(|logic, board| {
+            let cells = [Rank::Value(9), Rank::Value(9), Rank::Value(8), Rank::Value(8)];
+            for (i, rank) in cells.into_iter().enumerate() {
+                let suit = if i % 2 == 0 { Suit::Spades } else { Suit::Clubs };
+                board.get_pile_mut(logic.cell_ids[i]).add_card(Card::new(suit, rank));
+            }
+
+            let tableau = [Rank::King, Rank::King, Rank::Queen, Rank::Queen, Rank::Jack, Rank::Jack, Rank::Value(10), Rank::Value(10)];
+            for (i, rank) in tableau.into_iter().enumerate() {
+                let suit = if i % 2 == 0 { Suit::Spades } else { Suit::Clubs };
+                board.get_pile_mut(logic.tableau_ids[i]).add_card(Card::new(suit, rank));
+            }
+        });
+
+        assert_eq!(game.get_status(), GameStatus::Lost);
+    }
+
+    #[test]
+    fn finished_callback_fires_once() {
+        let mut game = empty_freecell(fill_foundations);
+
+        // Picking up a king and dropping it back re-checks the finished board
+        let last_foundation = FOUNDATIONS[3];
+
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        game.set_on_finished(move |status| {
+            assert_eq!(status, GameStatus::Won);
+            counter.set(counter.get() + 1);
+        });
+
+        for _ in 0..2 {
+            game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: last_foundation, card_idx: 12 }));
+            game.handle_event(GameEvent::DropEvent(Some(last_foundation)));
+        }
+
+        assert_eq!(calls.get(), 1);
+    }
+
+    fn pile_lengths(game: &Game) -> Vec<usize> {
+        game.board_ref().pile_iter().map(|p| p.len()).collect()
+    }
+
+    #[test]
+    fn undo_and_redo_a_move() {
+        let mut game = Game::arranged(FreeCell::default(), 1, |_, _| {});
+        let (cell, column) = (CELLS[0], COLUMNS[0]);
+
+        let before = pile_lengths(&game);
+        let top_idx = game.board_ref().get_pile(column).len() - 1;
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: column, card_idx: top_idx }));
+        game.handle_event(GameEvent::DropEvent(Some(cell)));
+        let after = pile_lengths(&game);
+
+        assert_ne!(before, after);
+        assert!(game.can_undo());
+        assert!(game.undo());
+        assert_eq!(pile_lengths(&game), before);
+        assert!(!game.can_undo());
+        assert!(game.redo());
+        assert_eq!(pile_lengths(&game), after);
+        assert!(!game.can_redo());
+    }
+
+    #[test]
+    fn returned_selection_is_not_recorded() {
+        let mut game = Game::create_with_logic(Box::new(FreeCell::default()));
+        game.setup();
+
+        let column = game.board_ref().pile_iter().last().unwrap().id;
+        let top_idx = game.board_ref().get_pile(column).len() - 1;
+        for target in [None, Some(column)] {
+            game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: column, card_idx: top_idx }));
+            game.handle_event(GameEvent::DropEvent(target));
+        }
+
+        assert!(!game.can_undo());
+    }
+
+    #[test]
+    fn fresh_deal_moves_top_cards_to_every_cell() {
+        let game = Game::arranged(FreeCell::default(), 1, |logic, board| {
+            for mv in logic.legal_moves(board).iter() {
+                assert!(mv.card_count(board) <= logic.supermove_limit(board, mv.target));
+            }
+        });
+
+        let moves = game.legal_moves();
+        for column in COLUMNS {
+            let top_idx = game.board_ref().get_pile(column).len() - 1;
+            for cell in CELLS {
+                let mv = Move { source: CardLocation { pile_id: column, card_idx: top_idx }, target: cell };
+                assert!(moves.contains(&mv));
+            }
+        }
+    }
+
+    #[test]
+    fn runs_move_within_the_supermove_limit() {
+        let (source, target, spare) = (COLUMNS[0], COLUMNS[1], COLUMNS[2]);
+        let run = Move { source: CardLocation { pile_id: source, card_idx: 0 }, target };
+
+        let mut game = empty_freecell(|logic, board| {
+            for card in [Card::new(Suit::Spades, Rank::Value(8)), Card::new(Suit::Hearts, Rank::Value(7)), Card::new(Suit::Spades, Rank::Value(6))] {
+                board.get_pile_mut(source).add_card(card);
+            }
+            board.get_pile_mut(target).add_card(Card::new(Suit::Hearts, Rank::Value(9)));
+            for id in COLUMNS[3..].iter() {
+                board.get_pile_mut(*id).add_card(Card::new(Suit::Clubs, Rank::King));
+            }
+            for id in CELLS[..3].iter() {
+                board.get_pile_mut(*id).add_card(Card::new(Suit::Diamonds, Rank::King));
+            }
+
+            // One free cell and one empty column move four cards, but an empty target column is no help
+            assert_eq!(logic.supermove_limit(board, target), 4);
+            assert_eq!(logic.supermove_limit(board, spare), 2);
+            assert!(logic.legal_moves(board).contains(&run));
+
+            board.get_pile_mut(spare).add_card(Card::new(Suit::Clubs, Rank::King));
+        });
+        assert!(!game.legal_moves().contains(&run));
+
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: source, card_idx: 0 }));
+        game.handle_event(GameEvent::DropEvent(Some(target)));
+        assert_eq!(game.board_ref().get_pile(source).len(), 3);
+        assert!(!game.can_undo());
+    }
+
+    #[test]
+    fn run_moves_as_a_unit() {
+        let (source, target) = (COLUMNS[0], COLUMNS[1]);
+        let mut game = empty_freecell(|_, board| {
+            for card in [Card::new(Suit::Spades, Rank::Value(8)), Card::new(Suit::Hearts, Rank::Value(7)), Card::new(Suit::Spades, Rank::Value(6))] {
+                board.get_pile_mut(source).add_card(card);
+            }
+            board.get_pile_mut(target).add_card(Card::new(Suit::Hearts, Rank::Value(9)));
+        });
+
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: source, card_idx: 0 }));
+        game.handle_event(GameEvent::DropEvent(Some(target)));
+        assert!(game.board_ref().get_pile(source).is_empty());
+        assert_eq!(game.board_ref().get_pile(target).len(), 4);
+
+        assert!(game.undo());
+        assert_eq!(game.board_ref().get_pile(source).len(), 3);
+    }
+
+    #[test]
+    fn runs_do_not_move_onto_foundations() {
+        let (column, foundation) = (COLUMNS[0], FOUNDATIONS[0]);
+        let game = empty_freecell(|_, board| {
+            board.get_pile_mut(foundation).add_card(Card::new(Suit::Spades, Rank::Ace));
+            board.get_pile_mut(column).add_card(Card::new(Suit::Spades, Rank::Value(2)));
+            board.get_pile_mut(column).add_card(Card::new(Suit::Hearts, Rank::Ace));
+        });
+
+        // The foundation only looks at the two, so the game has to turn the run away
+        let mv = Move { source: CardLocation { pile_id: column, card_idx: 0 }, target: foundation };
+        assert!(game.board_ref().accepts_move(&mv));
+        assert!(!game.legal_moves().contains(&mv));
+    }
+
+    fn auto_play_game(auto_play: AutoPlay) -> (Game, PileId, PileId) {
+        let (first, second, third) = (COLUMNS[0], COLUMNS[1], COLUMNS[2]);
+        let mut game = empty_freecell(|_, board| {
+            for card in [Card::new(Suit::Spades, Rank::Value(3)), Card::new(Suit::Hearts, Rank::Value(2)), Card::new(Suit::Clubs, Rank::King)] {
+                board.get_pile_mut(first).add_card(card);
+            }
+            board.get_pile_mut(second).add_card(Card::new(Suit::Hearts, Rank::Ace));
+        });
+        game.set_auto_play(auto_play);
+
+        // Moving the king off exposes the two of hearts
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: first, card_idx: 2 }));
+        game.handle_event(GameEvent::DropEvent(Some(third)));
+
+        (game, first, second)
+    }
+
+    #[test]
+    fn auto_play_policies() {
+        let (game, _, second) = auto_play_game(AutoPlay::None);
+        assert_eq!(game.history()[0].steps.len(), 1);
+        assert_eq!(game.board_ref().get_pile(second).len(), 1);
+
+        // The two of hearts isn't safe while the black aces are still out
+        let (game, first, second) = auto_play_game(AutoPlay::SafeOnly);
+        assert_eq!(game.history()[0].steps.len(), 2);
+        assert!(game.board_ref().get_pile(second).is_empty());
+        assert_eq!(game.board_ref().get_pile(first).len(), 2);
+
+        let (game, first, _) = auto_play_game(AutoPlay::Always);
+        assert_eq!(game.history()[0].steps.len(), 3);
+        assert_eq!(game.board_ref().get_pile(first).len(), 1);
+    }
+
+    #[test]
+    fn auto_play_is_undone_with_the_move() {
+        let (mut game, first, second) = auto_play_game(AutoPlay::Always);
+        let foundation = game.board_ref().pile_iter().find(|p| p.len() == 2).unwrap().id();
+
+        assert!(game.undo());
+        assert_eq!(game.board_ref().get_pile(first).len(), 3);
+        assert_eq!(game.board_ref().get_pile(second).len(), 1);
+        assert!(game.board_ref().get_pile(foundation).is_empty());
+    }
+
+    #[test]
+    fn send_picks_foundation_then_tableau_then_cell() {
+        let (first, second, third) = (COLUMNS[0], COLUMNS[1], COLUMNS[2]);
+        let (cell, foundation) = (CELLS[0], FOUNDATIONS[0]);
+        let mut game = empty_freecell(|_, board| {
+            board.get_pile_mut(first).add_card(Card::new(Suit::Hearts, Rank::Value(5)));
+            board.get_pile_mut(second).add_card(Card::new(Suit::Spades, Rank::Value(6)));
+            board.get_pile_mut(third).add_card(Card::new(Suit::Clubs, Rank::King));
+            board.get_pile_mut(third).add_card(Card::new(Suit::Spades, Rank::Ace));
+        });
+
+        let (five, ace, king) = (CardLocation { pile_id: first, card_idx: 0 }, CardLocation { pile_id: third, card_idx: 1 }, CardLocation { pile_id: third, card_idx: 0 });
+
+        // Empty columns are passed over for the six of spades
+        assert_eq!(game.best_destination(five), Some(second));
+        assert_eq!(game.best_destination(ace), Some(foundation));
+
+        game.handle_event(GameEvent::SendEvent(ace));
+        assert_eq!(game.board_ref().get_pile(foundation).len(), 1);
+
+        game.handle_event(GameEvent::SendEvent(king));
+        assert_eq!(game.board_ref().get_pile(cell).len(), 1);
+        assert_eq!(game.history().len(), 2);
+    }
+
+    #[test]
+    fn hints_cycle_from_best_to_worst() {
+        let (first, second, third) = (COLUMNS[0], COLUMNS[1], COLUMNS[2]);
+        let foundation = FOUNDATIONS[0];
+        let mut game = empty_freecell(|_, board| {
+            board.get_pile_mut(first).add_card(Card::new(Suit::Hearts, Rank::Ace));
+            board.get_pile_mut(second).add_card(Card::new(Suit::Spades, Rank::Value(6)));
+            board.get_pile_mut(third).add_card(Card::new(Suit::Hearts, Rank::Value(5)));
+        });
+
+        let ranked = game.ranked_moves();
+
+        // Each card goes to one empty cell and whatever it builds on, as a lone card gains nothing from another column
+        assert_eq!(ranked.len(), 5);
+
+        let ace_home = Move { source: CardLocation { pile_id: first, card_idx: 0 }, target: foundation };
+        let five_on_six = Move { source: CardLocation { pile_id: third, card_idx: 0 }, target: second };
+        assert_eq!(game.hint(), Some(ace_home));
+        assert_eq!(game.hint(), Some(five_on_six));
+        for _ in 2..ranked.len() {
+            game.hint();
+        }
+        assert_eq!(game.hint(), Some(ace_home));
+
+        // Making a move starts the suggestions over
+        game.hint();
+        game.handle_event(GameEvent::SendEvent(CardLocation { pile_id: first, card_idx: 0 }));
+        assert_eq!(game.hint(), Some(five_on_six));
+    }
+
+    fn layout(game: &Game) -> Vec<Vec<(u8, u8)>> {
+        game.board_ref().pile_iter()
+            .map(|p| p.card_iter().map(|c| (c.suit as u8, c.get_rank_value())).collect())
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_deal() {
+        let mut first = Game::create_with_logic(Box::new(FreeCell::default()));
+        let mut second = Game::create_with_logic(Box::new(FreeCell::default()));
+        first.setup_with_seed(11982);
+        second.setup_with_seed(11982);
+
+        assert_eq!(first.seed(), 11982);
+        assert_eq!(layout(&first), layout(&second));
+
+        second.setup_with_seed(617);
+        assert_ne!(layout(&first), layout(&second));
+    }
+
+    #[test]
+    fn seeded_deal_is_pinned() {
+        // Guards against dependency updates quietly changing what a seed deals
+        let mut game = Game::create_with_logic(Box::new(FreeCell::default()));
+        game.setup_with_seed(1);
+
+        let first_column = layout(&game)[8].clone();
+        assert_eq!(first_column, vec![(2, 3), (0, 13), (2, 5), (3, 9), (0, 6), (1, 4), (0, 3)]);
+    }
+
+    fn microsoft_rows(deal_number: u32) -> Vec<String> {
+        let game = FreeCell::microsoft_deal(deal_number);
+        let columns: Vec<Vec<String>> = game.board_ref().pile_iter().skip(8)
+            .map(|p| p.card_iter().map(|c| {
+                let rank = "A23456789TJQK".as_bytes()[c.get_rank_value() as usize - 1] as char;
+                let suit = match c.suit { Suit::Clubs => 'C', Suit::Diamonds => 'D', Suit::Hearts => 'H', Suit::Spades => 'S' };
+                format!("{rank}{suit}")
+            }).collect())
+            .collect();
+
+        (0..7).map(|row| {
+            columns.iter().filter_map(|column| column.get(row).cloned()).collect::<Vec<_>>().join(" ")
+        }).collect()
+    }
+
+    #[test]
+    fn microsoft_deal_1() {
+        assert_eq!(microsoft_rows(1), vec![
+            "JD 2D 9H JC 5D 7H 7C 5H",
+            "KD KC 9S 5S AD QC KH 3H",
+            "2S KS 9D QD JS AS AH 3C",
+            "4C 5C TS QH 4H AC 4D 7S",
+            "3S TD 4S TH 8H 2C JH 7D",
+            "6D 8S 8D QS 6C 3D 8C TC",
+            "6S 9C 2H 6H",
+        ]);
+    }
+
+    #[test]
+    fn microsoft_deal_617() {
+        assert_eq!(microsoft_rows(617), vec![
+            "7D AD 5C 3S 5S 8C 2D AH",
+            "TD 7S QD AC 6D 8H AS KH",
+            "TH QC 3H 9D 6S 8D 3D TC",
+            "KD 5H 9S 3C 8S 7H 4D JS",
+            "4C QS 9C 9H 7C 6H 2C 2S",
+            "4S TS 2H 5D JC 6C JH QH",
+            "JD KS KC 4H",
+        ]);
+    }
+
+    #[test]
+    fn microsoft_deal_number_is_the_seed() {
+        let mut game = FreeCell::microsoft_deal(11982);
+        assert_eq!(game.seed(), 11982);
+
+        game.setup();
+        assert!((1..=FreeCell::MICROSOFT_DEAL_COUNT as u64).contains(&game.seed()));
+
+        // Seeds past 32 bits deal the Microsoft deal they wrap round to, and go by its number
+        game.setup_with_seed((1 << 32) + 617);
+        assert_eq!(game.board_ref(), FreeCell::microsoft_deal(617).board_ref());
+        assert_eq!(game.seed(), 617);
+    }
+
+    #[test]
+    fn shuffling_between_cells_gets_stuck() {
+        let mut game = FreeCell::microsoft_deal(1);
+        let column = game.logic.tableau_ids()[0];
+        let (first, second) = (game.logic.cell_ids()[0], game.logic.cell_ids()[1]);
+        let top = CardLocation { pile_id: column, card_idx: game.board_ref().get_pile(column).len() - 1 };
+
+        let drag = |game: &mut Game, source: CardLocation, target: PileId| {
+            game.handle_event(GameEvent::SelectEvent(source));
+            game.handle_event(GameEvent::DropEvent(Some(target)));
+        };
+
+        // The cells are interchangeable, so every shuffle comes back to the position the first move made
+        drag(&mut game, top, first);
+        drag(&mut game, CardLocation { pile_id: first, card_idx: 0 }, second);
+        assert_eq!(game.get_status(), GameStatus::Ongoing);
+        drag(&mut game, CardLocation { pile_id: second, card_idx: 0 }, first);
+        assert_eq!(game.get_status(), GameStatus::Stuck(StuckReason::RepeatedPosition));
+
+        assert!(game.undo());
+        assert_eq!(game.get_status(), GameStatus::Ongoing);
+
+        game.set_repetition_limit(None);
+        assert!(game.redo());
+        assert_eq!(game.get_status(), GameStatus::Ongoing);
+    }
+
+    #[test]
+    fn position_text_matches_fc_solve() {
+        let game = FreeCell::microsoft_deal(1);
+        let text = game.position_text().to_string();
+
+        let columns: Vec<&str> = text.lines().filter(|line| line.starts_with(':')).collect();
+        assert_eq!(columns, vec![
+            ": JD KD 2S 4C 3S 6D 6S",
+            ": 2D KC KS 5C TD 8S 9C",
+            ": 9H 9S 9D TS 4S 8D 2H",
+            ": JC 5S QD QH TH QS 6H",
+            ": 5D AD JS 4H 8H 6C",
+            ": 7H QC AS AC 2C 3D",
+            ": 7C KH AH 4D JH 8C",
+            ": 5H 3H 3C 7S 7D TC",
+        ]);
+        assert!(text.starts_with("Freecell:\nFreecell:\nFreecell:\nFreecell:\nFoundation:\n"));
+
+        let mut copy = FreeCell::microsoft_deal(2);
+        copy.set_position(&format!("{:#}", game.position_text())).unwrap();
+        assert_eq!(copy.board_ref(), game.board_ref());
+        assert!(copy.history().is_empty());
+
+        let misplaced = text.replacen("Freecell:", ":", 1);
+        assert_eq!(copy.set_position(&misplaced), Err(ParsePositionError::WrongPile { line: 1 }));
+    }
+}
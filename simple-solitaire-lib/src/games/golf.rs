@@ -0,0 +1,220 @@
+use crate::board::{ActionItemId, Board, BoardItemStyle, BoardLocation, PileFlow, PileId};
+use crate::board::pile_logic::{AcceptLogic, Availability, AutoFlip, EmptyAcceptLogic, RankOrdering, SuitOrdering};
+use crate::cards::Deck;
+use crate::games::{GameLogic, GameStatus};
+use crate::journal::{JournalEntry, MoveStep};
+
+/// Golf: seven columns of five face-up cards, played one at a time onto the waste when they're a rank higher or
+/// lower than its top card, whatever the suit. Kings and aces aren't next to each other unless wrapping is turned
+/// on. When nothing fits, the stock deals its next card onto the waste; it isn't turned back over once it runs out.
+pub struct Golf {
+    wrap: bool,
+    stock_id: PileId,
+    waste_id: PileId,
+    tableau_ids: Vec<PileId>,
+    stock_action: ActionItemId,
+}
+
+impl Golf {
+    /// Whether kings and aces can be played onto each other. They can't by default.
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+}
+
+impl Default for Golf {
+    fn default() -> Self {
+        Self {
+            wrap: false,
+            stock_id: PileId(0),
+            waste_id: PileId(0),
+            tableau_ids: Vec::new(),
+            stock_action: ActionItemId(0),
+        }
+    }
+}
+
+/// The score for a run of cards played onto the waste: each is worth one more than the card before it, and
+/// dealing from the stock starts the count again.
+pub(super) fn chain_score(history: &[JournalEntry], stock_id: PileId, waste_id: PileId) -> i32 {
+    let mut chain = 0;
+    let mut score = 0;
+
+    for entry in history {
+        match entry.steps.first() {
+            Some(MoveStep::Transfer { source, .. }) if *source == stock_id => chain = 0,
+            Some(MoveStep::Transfer { target, .. }) if *target == waste_id => {
+                chain += 1;
+                score += chain;
+            }
+            _ => {}
+        }
+    }
+
+    score
+}
+
+impl GameLogic for Golf {
+    fn setup(&mut self, board: &mut Board, seed: u64) {
+        self.tableau_ids.clear();
+
+        // Stock
+        let loc = BoardLocation { x: 0, y: 0 };
+        self.stock_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::None)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_auto_flip(AutoFlip::Never)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+        self.stock_action = board.create_action_item(loc, BoardItemStyle::Empty);
+
+        // Waste
+        let rank_ordering = match self.wrap {
+            true => RankOrdering::AdjacentWrapping,
+            false => RankOrdering::Adjacent,
+        };
+        let loc = BoardLocation { x: 1, y: 0 };
+        self.waste_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::None)
+                .with_suit_ordering(SuitOrdering::Any)
+                .with_rank_ordering(rank_ordering)
+                .with_accept(AcceptLogic::Ordered)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+
+        // Tableau
+        for i in 0..7 {
+            let loc = BoardLocation { x: i, y: 1 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_pile_flow(PileFlow::Down)
+                    .with_availability(Availability::Top)
+                    .with_accept(AcceptLogic::None)
+                    .with_empty_accept(EmptyAcceptLogic::None)
+                    .with_pile_style(BoardItemStyle::Empty)
+            });
+
+            self.tableau_ids.push(id);
+        }
+
+        let mut deck = Deck::single_deck();
+        deck.shuffle(seed);
+
+        for _ in 0..5 {
+            for id in &self.tableau_ids {
+                let card = deck.deal_card().expect("A single deck covers the tableau");
+                board.get_pile_mut(*id).add_card(card);
+            }
+        }
+
+        // The waste starts with a card of its own to play onto
+        let card = deck.deal_card().expect("A single deck has a card for the waste");
+        board.get_pile_mut(self.waste_id).add_card(card);
+
+        while let Some(card) = deck.deal_card() {
+            board.get_pile_mut(self.stock_id).add_card(card.turned_over());
+        }
+    }
+
+    fn tableau_ids(&self) -> &[PileId] {
+        &self.tableau_ids
+    }
+
+    fn get_status(&self, board: &Board) -> GameStatus {
+        if self.tableau_ids.iter().all(|id| board.get_pile(*id).is_empty()) {
+            return GameStatus::Won;
+        }
+
+        match self.legal_moves(board).is_empty() && board.get_pile(self.stock_id).is_empty() {
+            true => GameStatus::Lost,
+            false => GameStatus::Ongoing,
+        }
+    }
+
+    fn on_action(&self, board: &Board, action: ActionItemId) -> Vec<MoveStep> {
+        if action != self.stock_action || board.get_pile(self.stock_id).is_empty() { return Vec::new() }
+
+        vec![MoveStep::Transfer { source: self.stock_id, target: self.waste_id, count: 1 }]
+    }
+
+    fn score(&self, _board: &Board, history: &[JournalEntry]) -> i32 {
+        chain_score(history, self.stock_id, self.waste_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::CardLocation;
+    use crate::games::{Game, GameEvent};
+
+    // Pile ids are handed out in creation order: stock, waste, then the columns
+    fn golf_game(position: &str) -> Game {
+        let mut game = Game::create_with_logic(Box::new(Golf::default()));
+        game.setup_with_seed(1);
+        game.set_position(position).unwrap();
+        game
+    }
+
+    fn play_top(game: &mut Game, pile_id: PileId) {
+        let card_idx = game.board_ref().get_pile(pile_id).len() - 1;
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id, card_idx }));
+        game.handle_event(GameEvent::DropEvent(Some(PileId(1))));
+    }
+
+    #[test]
+    fn runs_onto_the_waste_score_as_a_chain() {
+        let mut game = golf_game("
+            Pile: [2C] [3C]
+            Pile: 5H
+            : KS 4D
+            : 6C
+            : 9D
+            : QH
+            : AD
+            : 7S 3S
+            : 2H
+        ");
+
+        play_top(&mut game, PileId(2));
+        play_top(&mut game, PileId(7));
+        assert_eq!(game.board_ref().get_pile(PileId(1)).len(), 3);
+        assert_eq!(game.score(), 1 + 2);
+
+        // The king is no use on a three
+        play_top(&mut game, PileId(2));
+        assert_eq!(game.board_ref().get_pile(PileId(2)).len(), 1);
+
+        // Dealing from the stock starts a new chain
+        game.handle_event(GameEvent::ActionEvent(ActionItemId(0)));
+        play_top(&mut game, PileId(8));
+        assert_eq!(game.score(), 1 + 2 + 1);
+
+        assert!(game.undo());
+        assert_eq!(game.score(), 1 + 2);
+    }
+
+    #[test]
+    fn out_of_cards_and_moves_is_lost() {
+        let game = golf_game("
+            Pile:
+            Pile: 5H
+            : 9D
+            : 6C 2S
+            :
+            :
+            :
+            :
+            :
+        ");
+
+        assert_eq!(game.get_status(), GameStatus::Lost);
+    }
+}
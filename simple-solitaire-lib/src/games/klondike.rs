@@ -0,0 +1,304 @@
+use crate::board::{ActionItemId, Board, BoardItemStyle, BoardLocation, PileFlow, PileId};
+use crate::board::pile_logic::{AcceptLogic, Availability, AutoFlip, EmptyAcceptLogic, RankOrdering, SuitOrdering};
+use crate::cards::{Deck, Rank, Suit};
+use crate::games::{deal_from_stock, GameLogic, GameStatus};
+use crate::journal::MoveStep;
+
+/// Klondike, dealing one or three cards at a time from the stock. Clicking the stock deals from it, and once it
+/// is empty the same spot turns the waste back over, as long as redeals remain.
+pub struct Klondike {
+    draw_count: usize,
+    redeal_limit: Option<usize>,
+    stock_id: PileId,
+    waste_id: PileId,
+    foundation_ids: Vec<PileId>,
+    tableau_ids: Vec<PileId>,
+    stock_action: ActionItemId,
+}
+
+impl Klondike {
+    fn new(draw_count: usize) -> Self {
+        Self {
+            draw_count,
+            redeal_limit: None,
+            stock_id: PileId(0),
+            waste_id: PileId(0),
+            foundation_ids: Vec::new(),
+            tableau_ids: Vec::new(),
+            stock_action: ActionItemId(0),
+        }
+    }
+
+    pub fn draw_one() -> Self {
+        Self::new(1)
+    }
+
+    pub fn draw_three() -> Self {
+        Self::new(3)
+    }
+
+    /// Limits how many times the waste can be turned back into the stock. Redeals are unlimited by default.
+    pub fn with_redeal_limit(mut self, redeal_limit: usize) -> Self {
+        self.redeal_limit = Some(redeal_limit);
+        self
+    }
+}
+
+impl Default for Klondike {
+    fn default() -> Self {
+        Self::draw_one()
+    }
+}
+
+impl GameLogic for Klondike {
+    fn setup(&mut self, board: &mut Board, seed: u64) {
+        self.foundation_ids.clear();
+        self.tableau_ids.clear();
+
+        // Stock
+        let loc = BoardLocation { x: 0, y: 0 };
+        self.stock_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::None)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_auto_flip(AutoFlip::Never)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+        self.stock_action = board.create_action_item(BoardLocation { x: 0, y: 0 }, BoardItemStyle::Empty);
+
+        // Waste
+        let loc = BoardLocation { x: 1, y: 0 };
+        self.waste_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::Top)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+
+        // Foundations
+        for i in 0..4 {
+            let suit = Suit::get_ordered()[i as usize];
+            let loc = BoardLocation { x: i + 3, y: 0 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_empty_accept(EmptyAcceptLogic::Only(Rank::Ace))
+                    .with_suit_ordering(SuitOrdering::Same)
+                    .with_rank_ordering(RankOrdering::Incrementing)
+                    .with_accept(AcceptLogic::Ordered)
+                    .with_pile_style(BoardItemStyle::Ace(suit))
+                    .with_pile_flow(PileFlow::Stack)
+                    .with_availability(Availability::Top)
+            });
+
+            self.foundation_ids.push(id);
+        }
+        board.make_interchangeable(&self.foundation_ids);
+
+        // Tableau
+        for i in 0..7 {
+            let loc = BoardLocation { x: i, y: 1 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_availability(Availability::Ordered)
+                    .with_suit_ordering(SuitOrdering::AlternatingColor)
+                    .with_rank_ordering(RankOrdering::Decrementing)
+                    .with_empty_accept(EmptyAcceptLogic::Only(Rank::King))
+                    .with_pile_style(BoardItemStyle::Empty)
+                    .with_pile_flow(PileFlow::Down)
+                    .with_accept(AcceptLogic::Ordered)
+            });
+
+            self.tableau_ids.push(id);
+        }
+
+        let mut deck = Deck::single_deck();
+        deck.shuffle(seed);
+
+        // Each row deals one card further right, and the first card of a row lands face up
+        for row in 0..7 {
+            for column in row..7 {
+                let card = deck.deal_card().expect("A single deck covers the tableau");
+                let card = if column == row { card } else { card.turned_over() };
+                board.get_pile_mut(self.tableau_ids[column]).add_card(card);
+            }
+        }
+
+        while let Some(card) = deck.deal_card() {
+            board.get_pile_mut(self.stock_id).add_card(card.turned_over());
+        }
+    }
+
+    fn foundation_ids(&self) -> &[PileId] {
+        &self.foundation_ids
+    }
+
+    fn tableau_ids(&self) -> &[PileId] {
+        &self.tableau_ids
+    }
+
+    fn get_status(&self, board: &Board) -> GameStatus {
+        let foundations_complete = self.foundation_ids.iter().all(|id| {
+            board.get_pile(*id).top_card().is_some_and(|card| card.rank == Rank::King)
+        });
+
+        if foundations_complete {
+            return GameStatus::Won;
+        }
+
+        match self.legal_moves(board).is_empty() && self.on_action(board, self.stock_action).is_empty() {
+            true => GameStatus::Lost,
+            false => GameStatus::Ongoing,
+        }
+    }
+
+    fn on_action(&self, board: &Board, action: ActionItemId) -> Vec<MoveStep> {
+        if action != self.stock_action { return Vec::new() }
+
+        deal_from_stock(board, self.stock_id, self.waste_id, self.draw_count, self.redeal_limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{CardLocation, Move};
+    use crate::cards::Card;
+    use crate::games::{Game, GameEvent, StuckReason};
+
+    fn card_key(game: &Game, pile_id: PileId) -> Option<(u8, u8)> {
+        game.board_ref().get_pile(pile_id).top_card().map(|c| (c.suit as u8, c.get_rank_value()))
+    }
+
+    fn klondike_game(logic: Klondike) -> (Game, PileId, PileId, ActionItemId) {
+        let mut game = Game::create_with_logic(Box::new(logic));
+        game.setup_with_seed(7);
+
+        // Pile and action ids are handed out in creation order
+        (game, PileId(0), PileId(1), ActionItemId(0))
+    }
+
+    #[test]
+    fn deal_layout() {
+        let (game, stock, ..) = klondike_game(Klondike::default());
+        let board = game.board_ref();
+
+        for (i, id) in game.logic().tableau_ids().iter().enumerate() {
+            let pile = board.get_pile(*id);
+            assert_eq!(pile.len(), i + 1);
+            assert_eq!(pile.card_iter().filter(|c| c.is_face_up()).count(), 1);
+            assert!(pile.top_card().unwrap().is_face_up());
+        }
+
+        let stock = board.get_pile(stock);
+        assert_eq!(stock.len(), 24);
+        assert!(stock.card_iter().all(|c| !c.is_face_up()));
+    }
+
+    #[test]
+    fn hint_prefers_uncovering_cards() {
+        let (mut game, _, _, _) = klondike_game(Klondike::draw_one());
+        let hint = game.hint().expect("A fresh deal has moves");
+
+        let ranked = game.ranked_moves();
+        let uncovers = |mv: &Move| mv.source.card_idx > 0;
+        assert_eq!(uncovers(&hint), ranked.iter().any(uncovers));
+    }
+
+    #[test]
+    fn draw_three_turns_three_cards_face_up() {
+        let (mut game, stock, waste, action) = klondike_game(Klondike::draw_three());
+
+        game.handle_event(GameEvent::ActionEvent(action));
+        assert_eq!(game.board_ref().get_pile(stock).len(), 21);
+        assert_eq!(game.board_ref().get_pile(waste).len(), 3);
+        assert!(game.board_ref().get_pile(waste).card_iter().all(|c| c.is_face_up()));
+
+        assert!(game.undo());
+        assert_eq!(game.board_ref().get_pile(stock).len(), 24);
+        assert!(game.board_ref().get_pile(stock).card_iter().all(|c| !c.is_face_up()));
+    }
+
+    #[test]
+    fn redeal_restores_stock_order() {
+        let (mut game, stock, waste, action) = klondike_game(Klondike::draw_one());
+
+        game.handle_event(GameEvent::ActionEvent(action));
+        let first_card = card_key(&game, waste);
+        for _ in 1..24 {
+            game.handle_event(GameEvent::ActionEvent(action));
+        }
+        assert!(game.board_ref().get_pile(stock).is_empty());
+
+        game.handle_event(GameEvent::ActionEvent(action));
+        assert_eq!(game.board_ref().redeal_count(), 1);
+        assert!(game.board_ref().get_pile(waste).is_empty());
+        assert!(game.board_ref().get_pile(stock).card_iter().all(|c| !c.is_face_up()));
+
+        game.handle_event(GameEvent::ActionEvent(action));
+        assert_eq!(card_key(&game, waste), first_card);
+    }
+
+    #[test]
+    fn redeal_limit_is_respected() {
+        let (mut game, stock, waste, action) = klondike_game(Klondike::draw_three().with_redeal_limit(0));
+
+        for _ in 0..8 {
+            game.handle_event(GameEvent::ActionEvent(action));
+        }
+        let entries = game.history().len();
+
+        game.handle_event(GameEvent::ActionEvent(action));
+        assert_eq!(game.history().len(), entries);
+        assert!(game.board_ref().get_pile(stock).is_empty());
+        assert_eq!(game.board_ref().get_pile(waste).len(), 24);
+    }
+
+    #[test]
+    fn foundations_take_one_card_at_a_time() {
+        let game = Game::arranged(Klondike::draw_one(), 7, |logic, board| {
+            let (foundation, column) = (logic.foundation_ids[0], logic.tableau_ids[0]);
+            board.get_pile_mut(column).take_from_card(0);
+            board.get_pile_mut(foundation).add_card(Card::new(Suit::Hearts, Rank::Ace));
+            board.get_pile_mut(column).add_card(Card::new(Suit::Hearts, Rank::Value(2)));
+            board.get_pile_mut(column).add_card(Card::new(Suit::Clubs, Rank::Ace));
+        });
+        let (foundation, column) = (game.logic().foundation_ids()[0], game.logic().tableau_ids()[0]);
+
+        // The two would be accepted on its own, but not with the ace riding along on top of it
+        let run = Move { source: CardLocation { pile_id: column, card_idx: 0 }, target: foundation };
+        assert!(game.board_ref().accepts_move(&run));
+        assert!(!game.logic().is_move_allowed(game.board_ref(), &run));
+    }
+
+    #[test]
+    fn recycling_the_stock_gets_stuck() {
+        let (mut game, _, _, action) = klondike_game(Klondike::draw_one());
+
+        // Each pass through the stock comes back to the deal
+        for pass in 1..=2 {
+            assert_eq!(game.get_status(), GameStatus::Ongoing, "pass {pass}");
+            for _ in 0..25 {
+                game.handle_event(GameEvent::ActionEvent(action));
+            }
+        }
+        assert_eq!(game.get_status(), GameStatus::Stuck(StuckReason::StockRecycled));
+    }
+
+    #[test]
+    fn position_text_keeps_face_down_cards() {
+        let (game, ..) = klondike_game(Klondike::draw_one());
+        let text = game.position_text().to_string();
+
+        let stock = text.lines().next().unwrap();
+        assert!(stock.starts_with("Pile: ["));
+        assert_eq!(stock.matches('[').count(), 24);
+
+        let (mut copy, ..) = klondike_game(Klondike::draw_three());
+        copy.set_position(&text).unwrap();
+        assert_eq!(copy.board_ref(), game.board_ref());
+    }
+}
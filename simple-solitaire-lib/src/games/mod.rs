@@ -0,0 +1,924 @@
+use std::cmp::PartialEq;
+use std::fmt;
+use std::time::Duration;
+use rand::Rng;
+use crate::board::{PileId, Board, CardLocation, Pile, Move, ActionItemId};
+use crate::cards::{Card, ParseCardError, Suit};
+use crate::journal::{JournalEntry, MoveJournal, MoveStep};
+use crate::solver::{GameSolver, SolveResult, SolverBudget};
+
+mod freecell;
+mod klondike;
+mod spider;
+mod pyramid;
+mod golf;
+mod tripeaks;
+mod canfield;
+mod yukon;
+
+pub use freecell::FreeCell;
+pub use klondike::Klondike;
+pub use spider::Spider;
+pub use pyramid::Pyramid;
+pub use golf::Golf;
+pub use tripeaks::TriPeaks;
+pub use canfield::Canfield;
+pub use yukon::Yukon;
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum GameStatus {
+    Ongoing,
+    Lost,
+    Won,
+    /// Moves can still be made, but the player keeps coming back to the same position. Playing on to a new
+    /// position, or undoing, makes the game ongoing again.
+    Stuck(StuckReason),
+}
+
+impl GameStatus {
+    /// Whether the game is over. A stuck game isn't, since the player can still find a way out of it.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, GameStatus::Lost | GameStatus::Won)
+    }
+}
+
+/// Why the game thinks the player is going round in circles.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum StuckReason {
+    /// The stock was gone through again without anything else changing.
+    StockRecycled,
+    /// Cards are being moved back and forth, such as a card shuffled between two free cells.
+    RepeatedPosition,
+}
+
+/// Which cards the game sends to the foundations by itself after each of the player's moves.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum AutoPlay {
+    #[default]
+    None,
+    Always,
+    /// Only cards no other card could still need to be built on, meaning both cards of the opposite color
+    /// one rank lower are already on the foundations.
+    SafeOnly,
+}
+
+/// How a new game picks its deal.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum DealMode {
+    #[default]
+    Random,
+    /// Only deals the solver proves can be won, peeking at the face-down cards. Up to `attempts` seeds are tried,
+    /// each within `budget`, and the last one is kept if none of them could be proven. With no attempts, a seed is
+    /// dealt without being tried.
+    Winnable { budget: SolverBudget, attempts: usize },
+}
+
+pub(crate) trait GameLogic {
+    // fn default() -> Self;
+    // fn create_game() -> Game {
+    //     Game { logic: Self::default(), board: Board::default(), selection: None }
+    // }
+    fn setup(&mut self, board: &mut Board, seed: u64);
+    fn get_status(&self, board: &Board) -> GameStatus;
+
+    fn legal_moves(&self, board: &Board) -> Vec<Move> {
+        board.legal_move_iter().filter(|mv| self.is_move_allowed(board, mv)).collect()
+    }
+
+    /// Whether the game allows a move the piles themselves would accept, for rules that depend on the rest of
+    /// the board rather than just the two piles involved. By default foundations only take one card at a time,
+    /// since a pile only checks the first card of a run against its own top card.
+    fn is_move_allowed(&self, board: &Board, mv: &Move) -> bool {
+        self.pile_kind(mv.target) != PileKind::Foundation || mv.card_count(board) == 1
+    }
+
+    /// The seed used when a game is dealt without one.
+    fn random_seed(&self) -> u64 {
+        rand::thread_rng().gen()
+    }
+
+    /// The seed a deal from `seed` is known by, for games that only tell fewer seeds apart. Seeds that give the
+    /// same deal give the same seed here.
+    fn deal_seed(&self, seed: u64) -> u64 {
+        seed
+    }
+
+    /// The steps to take when the player activates an action item, in order. Nothing happens if this is empty.
+    fn on_action(&self, _board: &Board, _action: ActionItemId) -> Vec<MoveStep> {
+        Vec::new()
+    }
+
+    /// The piles cards are played off to, in the order auto play tries them.
+    fn foundation_ids(&self) -> &[PileId] {
+        &[]
+    }
+
+    /// The piles cards are built down on.
+    fn tableau_ids(&self) -> &[PileId] {
+        &[]
+    }
+
+    /// The piles that each hold a single card out of the way.
+    fn cell_ids(&self) -> &[PileId] {
+        &[]
+    }
+
+    fn pile_kind(&self, pile_id: PileId) -> PileKind {
+        if self.foundation_ids().contains(&pile_id) { return PileKind::Foundation }
+        if self.tableau_ids().contains(&pile_id) { return PileKind::Tableau }
+        if self.cell_ids().contains(&pile_id) { return PileKind::Cell }
+
+        PileKind::Other
+    }
+
+    /// The legal moves, keeping only the first of any that take the same cards to empty piles of the same kind,
+    /// as it makes no difference which of those the cards end up in. Moving a whole pile into an empty one of the
+    /// same kind gets nowhere, so those are left out too.
+    fn distinct_moves(&self, board: &Board) -> Vec<Move> {
+        let mut empty_targets = Vec::new();
+
+        self.legal_moves(board).into_iter()
+            .filter(|mv| {
+                if !board.get_pile(mv.target).is_empty() { return true }
+
+                let kind = self.pile_kind(mv.target);
+                if mv.source.card_idx == 0 && self.pile_kind(mv.source.pile_id) == kind { return false }
+
+                let key = (mv.source, kind);
+                let first = !empty_targets.contains(&key);
+                empty_targets.push(key);
+                first
+            })
+            .collect()
+    }
+
+    /// A move the game makes on its own once the player's action is done, such as clearing away a finished run.
+    /// Called again after each one until there are none left.
+    fn auto_move(&self, _board: &Board) -> Option<MoveStep> {
+        None
+    }
+
+    /// The player's score, worked out afresh from the board and the moves that led to it so undoing takes points
+    /// back too. By default every card on the foundations is worth ten.
+    fn score(&self, board: &Board, _history: &[JournalEntry]) -> i32 {
+        self.foundation_ids().iter().map(|id| board.get_pile(*id).len() as i32 * 10).sum()
+    }
+
+    /// Whether the game is played by picking out cards, as in Pyramid, rather than moving them between piles.
+    /// Pick events are ignored otherwise.
+    fn plays_by_picking(&self) -> bool {
+        false
+    }
+
+    /// The steps that take the picked cards off the board, in order, or nothing if they don't go together. The
+    /// picked cards are available top cards, in the order they were picked.
+    fn on_pick(&self, _board: &Board, _picked: &[CardLocation]) -> Vec<MoveStep> {
+        Vec::new()
+    }
+
+    /// Every group of cards that `on_pick` would take off the board as it stands.
+    fn pick_choices(&self, _board: &Board) -> Vec<Vec<CardLocation>> {
+        Vec::new()
+    }
+}
+
+/// The steps for clicking a stock that deals `draw_count` cards onto the waste, turning the waste back over once the
+/// stock is empty while fewer than `redeal_limit` redeals have been made.
+pub(super) fn deal_from_stock(board: &Board, stock_id: PileId, waste_id: PileId, draw_count: usize, redeal_limit: Option<usize>) -> Vec<MoveStep> {
+    let stock = board.get_pile(stock_id);
+    if !stock.is_empty() {
+        let count = stock.len().min(draw_count);
+        return (0..count)
+            .map(|_| MoveStep::Transfer { source: stock_id, target: waste_id, count: 1 })
+            .collect();
+    }
+
+    let waste = board.get_pile(waste_id);
+    let can_redeal = redeal_limit.is_none_or(|limit| board.redeal_count() < limit);
+    if waste.is_empty() || !can_redeal { return Vec::new() }
+
+    // Moving the waste over one card at a time turns it back into the stock in its original order
+    let mut steps = vec![MoveStep::Redeal];
+    for _ in 0..waste.len() {
+        steps.push(MoveStep::Transfer { source: waste_id, target: stock_id, count: 1 });
+        steps.push(MoveStep::Flip { pile_id: stock_id });
+    }
+
+    steps
+}
+
+pub enum GameEvent {
+    SelectEvent(CardLocation),
+    DropEvent(Option<PileId>),
+    ActionEvent(ActionItemId),
+    /// Moves the card, and everything above it, to wherever `Game::best_destination` picks.
+    SendEvent(CardLocation),
+    /// Picks out a card, or puts it back if it was already picked. Once the picked cards go together, such as a
+    /// pair adding up to 13 in Pyramid, they're taken off the board. A card that doesn't go with the ones already
+    /// picked is picked on its own instead.
+    PickEvent(CardLocation),
+}
+
+/// The board as text, one line per pile in the order the game created them, with the cards from the bottom up
+/// in the usual two-character notation. Tableau piles start with a colon as in fc-solve's layouts, and other piles
+/// with what they're for: "Foundation:", "Freecell:", or "Pile:" for the likes of the stock. Face-down cards are
+/// in brackets, as in "[KD]". Formatting with `{:#}` shows suit symbols instead of initials.
+pub struct PositionText<'a> {
+    game: &'a Game,
+}
+
+impl fmt::Display for PositionText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let piles = self.game.board.pile_iter().zip(self.game.settled_piles());
+        for (pile, cards) in piles {
+            write!(f, "{}:", pile_prefix(self.game.logic.pile_kind(pile.id())))?;
+            for card in cards {
+                let text = match f.alternate() {
+                    true => format!("{card:#}"),
+                    false => card.to_string(),
+                };
+
+                match card.is_face_up() {
+                    true => write!(f, " {text}")?,
+                    false => write!(f, " [{text}]")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What goes before the colon on a pile's line of `PositionText`.
+fn pile_prefix(kind: PileKind) -> &'static str {
+    match kind {
+        PileKind::Foundation => "Foundation",
+        PileKind::Tableau => "",
+        PileKind::Cell => "Freecell",
+        PileKind::Other => "Pile",
+    }
+}
+
+/// Why a position couldn't be read. Lines are counted from one, leaving out blank ones.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ParsePositionError {
+    PileCount { expected: usize, found: usize },
+    /// The line doesn't start with the prefix for the pile it stands for.
+    WrongPile { line: usize },
+    BadCard { line: usize, error: ParseCardError },
+}
+
+impl fmt::Display for ParsePositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePositionError::PileCount { expected, found } => write!(f, "expected {expected} piles but found {found}"),
+            ParsePositionError::WrongPile { line } => write!(f, "line {line} is for a different kind of pile"),
+            ParsePositionError::BadCard { line, error } => write!(f, "line {line}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ParsePositionError {}
+
+/// What a pile is for, as far as telling moves apart and writing positions down go.
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub(crate) enum PileKind {
+    Foundation,
+    Tableau,
+    Cell,
+    Other,
+}
+
+struct SelectedPile {
+    cards: Vec<Card>,
+    source: PileId,
+}
+
+pub struct Game {
+    logic: Box<dyn GameLogic>,
+    board: Board,
+    selection: Option<SelectedPile>,
+    picked: Vec<CardLocation>,
+    status: GameStatus,
+    on_finished: Option<Box<dyn FnMut(GameStatus)>>,
+    journal: MoveJournal,
+    seed: u64,
+    auto_play: AutoPlay,
+    hints_given: usize,
+    proven_winnable: bool,
+    /// The position hash after each journal entry still done, following the one the game was dealt in.
+    positions: Vec<u64>,
+    repetition_limit: Option<usize>,
+    variant: &'static str,
+    elapsed: Duration,
+}
+
+impl Game {
+    pub(crate) fn create_with_logic(logic: Box<dyn GameLogic>) -> Self {
+        Game {
+            logic,
+            selection: None,
+            picked: Vec::new(),
+            board: Board::default(),
+            status: GameStatus::Ongoing,
+            on_finished: None,
+            journal: MoveJournal::default(),
+            seed: 0,
+            auto_play: AutoPlay::default(),
+            hints_given: 0,
+            proven_winnable: false,
+            positions: vec![Board::default().position_hash()],
+            repetition_limit: Some(3),
+            variant: "",
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Names the game after the `GameEntry` it was created from.
+    pub(crate) fn with_variant(mut self, variant: &'static str) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    fn select_cards(&mut self, source: CardLocation) {
+        let pile = self.board.get_pile_mut(source.pile_id);
+        let cards = pile.take_from_card(source.card_idx);
+        self.selection = Some(SelectedPile {
+            cards,
+            source: pile.id,
+        })
+    }
+
+    fn add_selection_to_pile(&mut self, target: PileId) {
+        if let Some(selection) = &mut self.selection {
+            let pile = self.board.get_pile_mut(target);
+            pile.add_cards(&mut selection.cards);
+
+            self.selection = None;
+        }
+    }
+
+    fn return_selection(&mut self) {
+        if let Some(id) = self.selection.as_ref().map(|s| s.source) {
+            self.add_selection_to_pile(id);
+        }
+    }
+
+    /// Journals a move the player has made, along with everything it caused.
+    fn record_move(&mut self, step: MoveStep) {
+        let mut entry = JournalEntry::new(step);
+        entry.steps.append(&mut self.board.auto_flip());
+
+        while let Some(step) = self.next_auto_play() {
+            self.apply_to_entry(&mut entry, step);
+        }
+
+        self.finish_entry(entry);
+    }
+
+    /// The first top card, going through the piles in order, that auto play would send to a foundation.
+    fn next_auto_play(&self) -> Option<MoveStep> {
+        if self.auto_play == AutoPlay::None { return None }
+
+        let foundation_ids = self.logic.foundation_ids();
+        self.board.pile_iter()
+            .filter(|pile| !pile.is_empty() && !foundation_ids.contains(&pile.id()))
+            .find_map(|pile| {
+                let source = CardLocation { pile_id: pile.id(), card_idx: pile.len() - 1 };
+                let card = pile.top_card()?;
+                if self.auto_play == AutoPlay::SafeOnly && !self.is_safe_to_play(card) { return None }
+
+                foundation_ids.iter()
+                    .map(|target| Move { source, target: *target })
+                    .find(|mv| self.board.accepts_move(mv) && self.logic.is_move_allowed(&self.board, mv))
+                    .map(|mv| MoveStep::Transfer { source: source.pile_id, target: mv.target, count: 1 })
+            })
+    }
+
+    /// Whether the cards of the other colour that could go on this one are already on the foundations. Ranks are
+    /// counted from whatever the foundations start from, which is the ace unless the deal picked another.
+    fn is_safe_to_play(&self, card: &Card) -> bool {
+        let foundations: Vec<&Pile> = self.logic.foundation_ids().iter().map(|id| self.board.get_pile(*id)).collect();
+        let base = foundations.iter()
+            .find_map(|pile| pile.card_iter().next())
+            .map_or(1, |card| card.get_rank_value());
+        let height = |card: &Card| (card.get_rank_value() + 13 - base) % 13 + 1;
+
+        let needed = height(card) - 1;
+        let foundation_tops: Vec<&Card> = foundations.iter().filter_map(|pile| pile.top_card()).collect();
+
+        Suit::get_ordered().iter()
+            .filter(|suit| suit.get_color() != card.get_color())
+            .all(|suit| {
+                needed == 0 || foundation_tops.iter().any(|top| top.suit == *suit && height(top) >= needed)
+            })
+    }
+
+    /// Applies the steps the game logic decided on and journals them as one player action.
+    fn perform(&mut self, steps: Vec<MoveStep>) {
+        if steps.is_empty() { return }
+
+        let mut entry = JournalEntry { steps: Vec::new() };
+        for step in steps {
+            self.apply_to_entry(&mut entry, step);
+        }
+
+        self.finish_entry(entry);
+    }
+
+    fn apply_to_entry(&mut self, entry: &mut JournalEntry, step: MoveStep) {
+        self.board.apply_step(&step);
+        entry.steps.push(step);
+        entry.steps.append(&mut self.board.auto_flip());
+    }
+
+    /// Lets the game make its own moves in response to the player's, then journals the lot.
+    fn finish_entry(&mut self, mut entry: JournalEntry) {
+        while let Some(step) = self.logic.auto_move(&self.board) {
+            self.apply_to_entry(&mut entry, step);
+        }
+
+        self.journal.record(entry);
+        self.positions.push(self.board.position_hash());
+        self.hints_given = 0;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.journal.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.journal.can_redo()
+    }
+
+    /// Takes back the last player action along with everything it caused. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        self.return_selection();
+
+        let Some(entry) = self.journal.undo() else { return false };
+        self.hints_given = 0;
+        self.picked.clear();
+        for step in entry.steps.iter().rev() {
+            self.board.revert_step(step);
+        }
+        self.positions.pop();
+
+        self.update_status();
+        true
+    }
+
+    /// Replays the last undone player action. Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        self.return_selection();
+
+        let Some(entry) = self.journal.redo() else { return false };
+        self.hints_given = 0;
+        self.picked.clear();
+        for step in entry.steps.iter() {
+            self.board.apply_step(step);
+        }
+        self.positions.push(self.board.position_hash());
+
+        self.update_status();
+        true
+    }
+
+    pub fn history(&self) -> &[JournalEntry] {
+        self.journal.entries()
+    }
+
+    /// Deals a new game from a random seed.
+    pub fn setup(&mut self) {
+        self.setup_with_seed(self.logic.random_seed());
+    }
+
+    /// Deals a new game. The same seed always gives the same deal.
+    pub fn setup_with_seed(&mut self, seed: u64) {
+        let seed = self.logic.deal_seed(seed);
+        self.board = Board::default();
+        self.selection = None;
+        self.picked.clear();
+        self.status = GameStatus::Ongoing;
+        self.journal = MoveJournal::default();
+        self.hints_given = 0;
+        self.seed = seed;
+        self.proven_winnable = false;
+        self.elapsed = Duration::ZERO;
+
+        self.logic.setup(&mut self.board, seed);
+        self.positions = vec![self.board.position_hash()];
+    }
+
+    /// Deals a new game from random seeds, the way the mode asks.
+    pub fn setup_with_mode(&mut self, mode: DealMode) {
+        let DealMode::Winnable { budget, attempts } = mode else { return self.setup() };
+
+        self.setup();
+        let solver = GameSolver::new(budget).with_peek();
+        for attempt in 0..attempts {
+            if attempt > 0 { self.setup() }
+            if let SolveResult::Solved(_) = solver.solve(self) {
+                self.proven_winnable = true;
+                return;
+            }
+        }
+    }
+
+    /// The seed the current game was dealt from. Where a game only tells fewer seeds apart, this is the one it
+    /// knows the deal by, such as the Microsoft deal number a larger seed wraps round to.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The name of the `GameEntry` the game was created from, or an empty string if it wasn't created from one.
+    pub fn variant(&self) -> &'static str {
+        self.variant
+    }
+
+    pub fn score(&self) -> i32 {
+        self.logic.score(&self.board, self.journal.entries())
+    }
+
+    /// How long the current game has been played for.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Counts time spent playing. The game keeps no clock of its own, so whoever is showing it passes the time
+    /// along. Time stops counting once the game is over.
+    pub fn add_elapsed(&mut self, time: Duration) {
+        if !self.status.is_finished() {
+            self.elapsed += time;
+        }
+    }
+
+    /// The board written out as text, see `PositionText`.
+    pub fn position_text(&self) -> PositionText<'_> {
+        PositionText { game: self }
+    }
+
+    /// Sets the cards out the way `PositionText` writes them, as a fresh game with no history. The text has to
+    /// list this game's piles in order, each with the prefix for what it's for.
+    pub fn set_position(&mut self, text: &str) -> Result<(), ParsePositionError> {
+        self.return_selection();
+
+        let lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+        let pile_ids: Vec<PileId> = self.board.pile_iter().map(|pile| pile.id()).collect();
+        if lines.len() != pile_ids.len() {
+            return Err(ParsePositionError::PileCount { expected: pile_ids.len(), found: lines.len() });
+        }
+
+        let mut board = self.board.clone();
+        for (idx, (text, id)) in lines.into_iter().zip(pile_ids).enumerate() {
+            let line = idx + 1;
+            let cards = text.split_once(':')
+                .filter(|(prefix, _)| prefix.trim() == pile_prefix(self.logic.pile_kind(id)))
+                .map(|(_, cards)| cards)
+                .ok_or(ParsePositionError::WrongPile { line })?;
+
+            let pile = board.get_pile_mut(id);
+            pile.take_from_card(0);
+            for token in cards.split_whitespace() {
+                let face_down = token.strip_prefix('[').and_then(|inner| inner.strip_suffix(']'));
+                let card: Card = face_down.unwrap_or(token).parse()
+                    .map_err(|error| ParsePositionError::BadCard { line, error })?;
+
+                pile.add_card(if face_down.is_some() { card.turned_over() } else { card });
+            }
+        }
+
+        self.board = board;
+        self.picked.clear();
+        self.journal = MoveJournal::default();
+        self.positions = vec![self.board.position_hash()];
+        self.hints_given = 0;
+        self.update_status();
+        Ok(())
+    }
+
+    /// The board with any cards the player is holding put back where they came from.
+    pub(crate) fn settled_board(&self) -> Board {
+        let mut board = self.board.clone();
+        if let Some(selection) = &self.selection {
+            board.get_pile_mut(selection.source).add_cards(&mut selection.cards.clone());
+        }
+
+        board
+    }
+
+    /// Every pile's cards from the bottom up, with any the player is holding put back where they came from.
+    pub(crate) fn settled_piles(&self) -> Vec<Vec<Card>> {
+        self.settled_board().pile_iter()
+            .map(|pile| pile.card_iter().cloned().collect())
+            .collect()
+    }
+
+    /// Puts back a saved game dealt from `seed`: the cards where they lay, the player actions that got them there,
+    /// and whether the deal was proven winnable. Returns false if they don't fit this game's layout, or taking the history back doesn't arrive at the
+    /// deal the seed gives, leaving a fresh deal.
+    pub(crate) fn restore(&mut self, seed: u64, piles: Vec<Vec<Card>>, history: Vec<JournalEntry>, elapsed: Duration, proven_winnable: bool) -> bool {
+        self.setup_with_seed(seed);
+
+        let pile_ids: Vec<PileId> = self.board.pile_iter().map(|pile| pile.id()).collect();
+        if piles.len() != pile_ids.len() { return false }
+
+        let mut board = self.board.clone();
+        for (id, cards) in pile_ids.into_iter().zip(piles) {
+            let pile = board.get_pile_mut(id);
+            pile.take_from_card(0);
+            for card in cards {
+                pile.add_card(card);
+            }
+        }
+        for _ in history.iter().flat_map(|entry| entry.steps.iter()).filter(|step| **step == MoveStep::Redeal) {
+            board.apply_step(&MoveStep::Redeal);
+        }
+
+        // Taking the history back checks it fits the cards, and gives the positions repetition is judged on
+        let mut earlier = board.clone();
+        let mut positions = vec![board.position_hash()];
+        for entry in history.iter().rev() {
+            for step in entry.steps.iter().rev() {
+                if !earlier.can_revert_step(step) { return false }
+                earlier.revert_step(step);
+            }
+            positions.push(earlier.position_hash());
+        }
+        positions.reverse();
+
+        // Taken all the way back, the cards have to lie exactly as the seed deals them, facing included
+        let dealt = earlier.pile_iter().zip(self.board.pile_iter())
+            .all(|(earlier, dealt)| earlier.card_iter().eq(dealt.card_iter()));
+        if !dealt { return false }
+
+        self.board = board;
+        self.journal = MoveJournal::with_entries(history);
+        self.positions = positions;
+        self.elapsed = elapsed;
+        self.proven_winnable = proven_winnable;
+        self.update_status();
+        true
+    }
+
+    /// Whether the solver proved the current deal can be won when it was dealt. Dealing again from `seed` gives
+    /// the same deal, so the proof can be checked.
+    pub fn is_proven_winnable(&self) -> bool {
+        self.proven_winnable
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_board(mut self, board: Board) -> Self {
+        self.positions = vec![board.position_hash()];
+        self.board = board;
+        self
+    }
+
+    /// Deals `logic` from `seed`, lets `arrange` set the cards out by hand, then starts the game from there. For
+    /// tests that need a position no deal gives; `arrange` gets the logic for its pile ids.
+    #[cfg(test)]
+    pub(crate) fn arranged<L: GameLogic + 'static>(mut logic: L, seed: u64, arrange: impl FnOnce(&L, &mut Board)) -> Self {
+        let mut board = Board::default();
+        logic.setup(&mut board, seed);
+        arrange(&logic, &mut board);
+
+        Self::create_with_logic(Box::new(logic)).with_board(board)
+    }
+
+    pub(crate) fn logic(&self) -> &dyn GameLogic {
+        self.logic.as_ref()
+    }
+
+    pub fn board_ref(&self) -> &Board {
+        &self.board
+    }
+
+    /// The cards picked out so far that haven't yet been taken off the board.
+    pub fn picked(&self) -> &[CardLocation] {
+        &self.picked
+    }
+
+    /// Whether cards are picked out with `GameEvent::PickEvent` rather than moved between piles.
+    pub fn plays_by_picking(&self) -> bool {
+        self.logic.plays_by_picking()
+    }
+
+    /// Every group of cards a pick event could take off the board. Empty while cards are selected.
+    pub fn pick_choices(&self) -> Vec<Vec<CardLocation>> {
+        match self.selection {
+            Some(_) => Vec::new(),
+            None => self.logic.pick_choices(&self.board),
+        }
+    }
+
+    /// The status of the board as it stands. A game is always `Ongoing` while cards are selected.
+    pub fn get_status(&self) -> GameStatus {
+        match self.selection {
+            Some(_) => GameStatus::Ongoing,
+            None => match self.logic.get_status(&self.board) {
+                GameStatus::Ongoing => self.stuck_reason().map_or(GameStatus::Ongoing, GameStatus::Stuck),
+                status => status,
+            },
+        }
+    }
+
+    /// How many times the same position may come round before the game counts as stuck, or `None` to never
+    /// call it stuck. Three by default.
+    pub fn set_repetition_limit(&mut self, limit: Option<usize>) {
+        self.repetition_limit = limit;
+        self.update_status();
+    }
+
+    /// Why the player is stuck, if the current position has come round as often as the repetition limit allows.
+    fn stuck_reason(&self) -> Option<StuckReason> {
+        let done = self.journal.entries();
+        debug_assert_eq!(self.positions.len(), done.len() + 1, "There's a position for the deal and each entry done");
+
+        let limit = self.repetition_limit?;
+        let current = *self.positions.last()?;
+        let first = self.positions.iter().position(|hash| *hash == current)?;
+        if self.positions[first..].iter().filter(|hash| **hash == current).count() < limit { return None }
+
+        // Only the entries made since the position first came round are part of the cycle
+        let cycle = &done[first..];
+        match cycle.iter().any(|entry| entry.steps.contains(&MoveStep::Redeal)) {
+            true => Some(StuckReason::StockRecycled),
+            false => Some(StuckReason::RepeatedPosition),
+        }
+    }
+
+    /// Every move that can currently be made. Empty while cards are selected.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        match self.selection {
+            Some(_) => Vec::new(),
+            None => self.logic.legal_moves(&self.board),
+        }
+    }
+
+    /// Suggests a move. Asking again without making a move suggests the next best one, going back round to the
+    /// best once every legal move has been suggested.
+    pub fn hint(&mut self) -> Option<Move> {
+        let ranked = self.ranked_moves();
+        if ranked.is_empty() { return None }
+
+        let hint = ranked[self.hints_given % ranked.len()];
+        self.hints_given += 1;
+
+        Some(hint)
+    }
+
+    /// Every legal move, best first, leaving out moves to interchangeable empty piles. Moves that score the same
+    /// keep the order `legal_moves` gives them.
+    pub fn ranked_moves(&self) -> Vec<Move> {
+        let mut moves: Vec<(i32, Move)> = self.logic.distinct_moves(&self.board).into_iter()
+            .map(|mv| (self.score_move(&mv), mv))
+            .collect();
+        moves.sort_by_key(|(score, _)| -score);
+
+        moves.into_iter().map(|(_, mv)| mv).collect()
+    }
+
+    fn score_move(&self, mv: &Move) -> i32 {
+        let source = self.board.get_pile(mv.source.pile_id);
+        let target = self.board.get_pile(mv.target);
+        let (source_kind, target_kind) = (self.logic.pile_kind(mv.source.pile_id), self.logic.pile_kind(mv.target));
+
+        let mut score = 0;
+
+        if target_kind == PileKind::Foundation { score += 50 }
+        if source_kind == PileKind::Foundation { score -= 50 }
+
+        // Turning over a face-down card is the only way to learn more about the deal
+        let uncovered = mv.source.card_idx.checked_sub(1).and_then(|idx| source.card_iter().nth(idx));
+        if uncovered.is_some_and(|card| !card.is_face_up()) { score += 40 }
+
+        if source_kind == PileKind::Tableau && mv.source.card_idx == 0 {
+            // Shifting a whole column into another empty one gets nowhere
+            match target_kind == PileKind::Tableau && target.is_empty() {
+                true => score -= 100,
+                false => score += 30,
+            }
+        }
+
+        if source_kind == PileKind::Cell { score += 25 }
+        if target_kind == PileKind::Cell { score -= 20 }
+        if target_kind == PileKind::Tableau && target.is_empty() { score -= 10 }
+
+        score
+    }
+
+    /// Where a `SendEvent` moves the card at `source` and everything above it. Foundations come first, then
+    /// tableau piles that already hold cards, then free cells. Within each kind the first pile in board order
+    /// that would take the cards wins, so the same position always sends the same way.
+    pub fn best_destination(&self, source: CardLocation) -> Option<PileId> {
+        if self.selection.is_some() { return None }
+
+        let foundations = self.logic.foundation_ids().iter();
+        let tableau = self.logic.tableau_ids().iter().filter(|id| !self.board.get_pile(**id).is_empty());
+        let cells = self.logic.cell_ids().iter();
+
+        foundations.chain(tableau).chain(cells)
+            .map(|target| Move { source, target: *target })
+            .find(|mv| self.board.accepts_move(mv) && self.logic.is_move_allowed(&self.board, mv))
+            .map(|mv| mv.target)
+    }
+
+    /// Sets which cards are sent to the foundations automatically after each move. Nothing is by default.
+    pub fn set_auto_play(&mut self, auto_play: AutoPlay) {
+        self.auto_play = auto_play;
+    }
+
+    pub fn auto_play(&self) -> AutoPlay {
+        self.auto_play
+    }
+
+    /// Registers a callback that fires once when the game is won or lost.
+    pub fn set_on_finished(&mut self, callback: impl FnMut(GameStatus) + 'static) {
+        self.on_finished = Some(Box::new(callback));
+    }
+
+    fn update_status(&mut self) {
+        let status = self.get_status();
+        let just_finished = status.is_finished() && !self.status.is_finished();
+        self.status = status;
+
+        if just_finished {
+            if let Some(callback) = &mut self.on_finished {
+                callback(status);
+            }
+        }
+    }
+
+    pub fn handle_event(&mut self, event: GameEvent) {
+        match event {
+            GameEvent::SelectEvent(card_location) => {
+                self.return_selection();
+
+                if self.board.is_card_available(card_location) {
+                    self.select_cards(card_location);
+                }
+            }
+            GameEvent::DropEvent(target) => {
+                if let Some(selection) = &self.selection {
+                    // The game judges the move with the cards back where they came from
+                    let source = CardLocation { pile_id: selection.source, card_idx: self.board.get_pile(selection.source).len() };
+                    let count = selection.cards.len();
+                    self.return_selection();
+
+                    if let Some(target) = target {
+                        let mv = Move { source, target };
+                        if self.board.accepts_move(&mv) && self.logic.is_move_allowed(&self.board, &mv) {
+                            let step = MoveStep::Transfer { source: source.pile_id, target, count };
+                            self.board.apply_step(&step);
+                            self.record_move(step);
+                        }
+                    }
+                }
+
+                self.update_status();
+            }
+            GameEvent::SendEvent(source) => {
+                self.return_selection();
+
+                if let Some(target) = self.best_destination(source) {
+                    let count = self.board.get_pile(source.pile_id).len() - source.card_idx;
+                    let step = MoveStep::Transfer { source: source.pile_id, target, count };
+                    self.board.apply_step(&step);
+                    self.record_move(step);
+                }
+
+                self.update_status();
+            }
+            GameEvent::ActionEvent(action) => {
+                self.return_selection();
+
+                let steps = self.logic.on_action(&self.board, action);
+                self.perform(steps);
+
+                self.update_status();
+            }
+            GameEvent::PickEvent(card_location) => {
+                self.return_selection();
+                if !self.logic.plays_by_picking() { return }
+
+                if let Some(idx) = self.picked.iter().position(|picked| *picked == card_location) {
+                    self.picked.remove(idx);
+                } else if self.board.is_card_available(card_location) {
+                    self.picked.push(card_location);
+
+                    let mut steps = self.logic.on_pick(&self.board, &self.picked);
+                    if steps.is_empty() && self.picked.len() > 1 {
+                        self.picked = vec![card_location];
+                        steps = self.logic.on_pick(&self.board, &self.picked);
+                    }
+                    if !steps.is_empty() {
+                        self.picked.clear();
+                        self.perform(steps);
+                    }
+                }
+
+                self.update_status();
+            }
+        }
+    }
+}
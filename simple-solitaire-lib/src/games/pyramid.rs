@@ -0,0 +1,274 @@
+use crate::board::{ActionItemId, Board, BoardItemStyle, BoardLocation, CardLocation, PileFlow, PileId};
+use crate::board::pile_logic::{AcceptLogic, Availability, AutoFlip, EmptyAcceptLogic};
+use crate::cards::Deck;
+use crate::games::{deal_from_stock, GameLogic, GameStatus};
+use crate::journal::MoveStep;
+
+const ROWS: usize = 7;
+
+/// Pyramid: 28 cards dealt face up in a triangle, each row half covering the one above. Picking two uncovered
+/// cards that add up to 13 discards them, and kings go on their own. The stock deals one card at a time onto the
+/// waste, whose top card can be paired too, and can be turned back over while redeals remain.
+pub struct Pyramid {
+    redeal_limit: Option<usize>,
+    stock_id: PileId,
+    waste_id: PileId,
+    /// Where picked cards go. It stands in for the foundations, as cards are done with once they get there.
+    discard_ids: [PileId; 1],
+    pyramid_ids: Vec<PileId>,
+    stock_action: ActionItemId,
+}
+
+impl Pyramid {
+    /// Limits how many times the waste can be turned back into the stock, or lifts the limit with `None`. The
+    /// stock can be gone through three times by default.
+    pub fn with_redeal_limit(mut self, redeal_limit: Option<usize>) -> Self {
+        self.redeal_limit = redeal_limit;
+        self
+    }
+
+    /// The card at `location` if it can be picked: the top of its pile, with nothing lying over it.
+    fn pickable(&self, board: &Board, location: CardLocation) -> Option<u8> {
+        let pile = board.get_pile(location.pile_id);
+        if location.card_idx + 1 != pile.len() || !board.is_card_available(location) { return None }
+
+        pile.top_card().map(|card| card.get_rank_value())
+    }
+}
+
+impl Default for Pyramid {
+    fn default() -> Self {
+        Self {
+            redeal_limit: Some(2),
+            stock_id: PileId(0),
+            waste_id: PileId(0),
+            discard_ids: [PileId(0)],
+            pyramid_ids: Vec::new(),
+            stock_action: ActionItemId(0),
+        }
+    }
+}
+
+impl GameLogic for Pyramid {
+    fn setup(&mut self, board: &mut Board, seed: u64) {
+        self.pyramid_ids.clear();
+
+        // Stock
+        let loc = BoardLocation { x: 0, y: 4 };
+        self.stock_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::None)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_auto_flip(AutoFlip::Never)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+        self.stock_action = board.create_action_item(loc, BoardItemStyle::Empty);
+
+        // Waste
+        let loc = BoardLocation { x: 1, y: 4 };
+        self.waste_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::Top)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+
+        // Discard
+        let loc = BoardLocation { x: 6, y: 4 };
+        self.discard_ids[0] = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::None)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+
+        // The pyramid, a pile for each card. Rows are half a square apart both ways, and later rows are drawn on
+        // top of earlier ones.
+        for row in 0..ROWS {
+            for idx in 0..=row {
+                let half_x = 2 * idx + ROWS - 1 - row;
+                let loc = BoardLocation { x: (half_x / 2) as u8, y: (row / 2) as u8 };
+                let id = board.create_pile(loc, |builder| {
+                    builder
+                        .with_pile_flow(PileFlow::Stack)
+                        .with_availability(Availability::Top)
+                        .with_accept(AcceptLogic::None)
+                        .with_empty_accept(EmptyAcceptLogic::None)
+                        .with_pile_style(BoardItemStyle::None)
+                        .with_offset((half_x % 2) as f32 * 0.5, (row % 2) as f32 * 0.5)
+                });
+
+                self.pyramid_ids.push(id);
+            }
+        }
+
+        // Each card is covered by the two below it
+        for row in 0..ROWS - 1 {
+            for idx in 0..=row {
+                let below = (row + 1) * (row + 2) / 2 + idx;
+                let covered = row * (row + 1) / 2 + idx;
+                board.cover(self.pyramid_ids[covered], &[self.pyramid_ids[below], self.pyramid_ids[below + 1]]);
+            }
+        }
+
+        let mut deck = Deck::single_deck();
+        deck.shuffle(seed);
+
+        for id in &self.pyramid_ids {
+            let card = deck.deal_card().expect("A single deck covers the pyramid");
+            board.get_pile_mut(*id).add_card(card);
+        }
+
+        while let Some(card) = deck.deal_card() {
+            board.get_pile_mut(self.stock_id).add_card(card.turned_over());
+        }
+    }
+
+    fn foundation_ids(&self) -> &[PileId] {
+        &self.discard_ids
+    }
+
+    fn get_status(&self, board: &Board) -> GameStatus {
+        if self.pyramid_ids.iter().all(|id| board.get_pile(*id).is_empty()) {
+            return GameStatus::Won;
+        }
+
+        match self.pick_choices(board).is_empty() && self.on_action(board, self.stock_action).is_empty() {
+            true => GameStatus::Lost,
+            false => GameStatus::Ongoing,
+        }
+    }
+
+    fn on_action(&self, board: &Board, action: ActionItemId) -> Vec<MoveStep> {
+        if action != self.stock_action { return Vec::new() }
+
+        deal_from_stock(board, self.stock_id, self.waste_id, 1, self.redeal_limit)
+    }
+
+    fn plays_by_picking(&self) -> bool {
+        true
+    }
+
+    fn on_pick(&self, board: &Board, picked: &[CardLocation]) -> Vec<MoveStep> {
+        if picked.len() > 2 { return Vec::new() }
+
+        let values: Option<Vec<u8>> = picked.iter().map(|location| self.pickable(board, *location)).collect();
+        if values.is_none_or(|values| values.iter().sum::<u8>() != 13) { return Vec::new() }
+
+        picked.iter()
+            .map(|location| MoveStep::Transfer { source: location.pile_id, target: self.discard_ids[0], count: 1 })
+            .collect()
+    }
+
+    fn pick_choices(&self, board: &Board) -> Vec<Vec<CardLocation>> {
+        let available: Vec<(CardLocation, u8)> = self.pyramid_ids.iter().chain([&self.waste_id])
+            .filter_map(|id| {
+                let location = CardLocation { pile_id: *id, card_idx: board.get_pile(*id).len().checked_sub(1)? };
+                self.pickable(board, location).map(|value| (location, value))
+            })
+            .collect();
+
+        let mut choices = Vec::new();
+        for (idx, (location, value)) in available.iter().enumerate() {
+            if *value == 13 {
+                choices.push(vec![*location]);
+            }
+            for (other, other_value) in &available[idx + 1..] {
+                if value + other_value == 13 {
+                    choices.push(vec![*location, *other]);
+                }
+            }
+        }
+
+        choices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{Game, GameEvent};
+
+    fn pyramid_game() -> Game {
+        let mut game = Game::create_with_logic(Box::new(Pyramid::default()));
+        game.setup_with_seed(3);
+        game
+    }
+
+    // Pile ids are handed out in creation order: stock, waste, discard, then the pyramid from the top down
+    const DISCARD: PileId = PileId(2);
+
+    fn pyramid_pile(row: usize, idx: usize) -> PileId {
+        PileId(3 + row * (row + 1) / 2 + idx)
+    }
+
+    fn top(pile_id: PileId) -> CardLocation {
+        CardLocation { pile_id, card_idx: 0 }
+    }
+
+    #[test]
+    fn only_uncovered_cards_can_be_picked() {
+        let mut game = pyramid_game();
+        assert!(game.board_ref().is_card_available(top(pyramid_pile(6, 3))));
+        assert!(!game.board_ref().is_card_available(top(pyramid_pile(5, 2))));
+
+        game.handle_event(GameEvent::PickEvent(top(pyramid_pile(5, 2))));
+        assert!(game.picked().is_empty());
+
+        // Clearing the two cards lying over it frees it up
+        let mut board = game.board_ref().clone();
+        board.get_pile_mut(pyramid_pile(6, 2)).take_from_card(0);
+        board.get_pile_mut(pyramid_pile(6, 3)).take_from_card(0);
+        assert!(board.is_card_available(top(pyramid_pile(5, 2))));
+        assert!(!board.is_card_available(top(pyramid_pile(5, 1))));
+    }
+
+    #[test]
+    fn cards_adding_up_to_13_are_discarded() {
+        let mut game = pyramid_game();
+        while game.pick_choices().iter().all(|choice| choice.len() == 1) {
+            game.handle_event(GameEvent::ActionEvent(ActionItemId(0)));
+        }
+        let before = game.board_ref().clone();
+        let pair = game.pick_choices().into_iter().find(|choice| choice.len() == 2).unwrap();
+
+        game.handle_event(GameEvent::PickEvent(pair[0]));
+        assert_eq!(game.picked(), &pair[..1]);
+        game.handle_event(GameEvent::PickEvent(pair[1]));
+        assert!(game.picked().is_empty());
+        assert_eq!(game.board_ref().get_pile(DISCARD).len(), 2);
+        assert_eq!(game.score(), 20);
+
+        assert!(game.undo());
+        assert_eq!(game.board_ref(), &before);
+    }
+
+    #[test]
+    fn cards_that_do_not_go_together_start_a_new_pick() {
+        let mut game = pyramid_game();
+        let bottom: Vec<CardLocation> = (0..ROWS).map(|idx| top(pyramid_pile(6, idx))).collect();
+        let value = |location: &CardLocation| {
+            game.board_ref().get_pile(location.pile_id).top_card().unwrap().get_rank_value()
+        };
+        let (first, second) = bottom.iter()
+            .flat_map(|first| bottom.iter().map(move |second| (*first, *second)))
+            .filter(|(first, second)| first != second && value(first) != 13 && value(second) != 13)
+            .find(|(first, second)| value(first) + value(second) != 13)
+            .unwrap();
+
+        game.handle_event(GameEvent::PickEvent(first));
+        game.handle_event(GameEvent::PickEvent(second));
+        assert_eq!(game.picked(), &[second]);
+
+        // Picking it again puts it back
+        game.handle_event(GameEvent::PickEvent(second));
+        assert!(game.picked().is_empty());
+        assert!(game.board_ref().get_pile(DISCARD).is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+use crate::board::{ActionItemId, Board, BoardItemStyle, BoardLocation, PileFlow, PileId};
+use crate::board::pile_logic::{AcceptLogic, Availability, AutoFlip, EmptyAcceptLogic, RankOrdering, SuitOrdering};
+use crate::cards::{Deck, Suit};
+use crate::games::{GameLogic, GameStatus};
+use crate::journal::MoveStep;
+
+const RUN_LENGTH: usize = 13;
+
+/// Spider, played with two decks' worth of cards in one, two or four suits. Runs are built down regardless of
+/// suit but only move together when they share one, and a finished King to Ace run is cleared off on its own.
+pub struct Spider {
+    suits: Vec<Suit>,
+    stock_id: PileId,
+    foundation_ids: Vec<PileId>,
+    tableau_ids: Vec<PileId>,
+    stock_action: ActionItemId,
+}
+
+impl Spider {
+    fn with_suits(suits: &[Suit]) -> Self {
+        Self {
+            suits: suits.to_vec(),
+            stock_id: PileId(0),
+            foundation_ids: Vec::new(),
+            tableau_ids: Vec::new(),
+            stock_action: ActionItemId(0),
+        }
+    }
+
+    pub fn one_suit() -> Self {
+        Self::with_suits(&[Suit::Spades])
+    }
+
+    pub fn two_suits() -> Self {
+        Self::with_suits(&[Suit::Spades, Suit::Hearts])
+    }
+
+    pub fn four_suits() -> Self {
+        Self::with_suits(&Suit::get_ordered())
+    }
+
+    fn has_completed_run(&self, board: &Board, pile_id: PileId) -> bool {
+        let pile = board.get_pile(pile_id);
+        let Some(top) = pile.top_card() else { return false };
+
+        pile.len() >= RUN_LENGTH && pile.card_iter_rev().take(RUN_LENGTH).enumerate().all(|(i, card)| {
+            card.is_face_up() && card.suit == top.suit && card.get_rank_value() as usize == i + 1
+        })
+    }
+}
+
+impl Default for Spider {
+    fn default() -> Self {
+        Self::one_suit()
+    }
+}
+
+impl GameLogic for Spider {
+    fn setup(&mut self, board: &mut Board, seed: u64) {
+        self.foundation_ids.clear();
+        self.tableau_ids.clear();
+
+        // Stock
+        let loc = BoardLocation { x: 0, y: 0 };
+        self.stock_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::None)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_auto_flip(AutoFlip::Never)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+        self.stock_action = board.create_action_item(BoardLocation { x: 0, y: 0 }, BoardItemStyle::Empty);
+
+        // Foundations, which only ever take completed runs
+        for i in 0..8 {
+            let loc = BoardLocation { x: i + 2, y: 0 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_pile_flow(PileFlow::Stack)
+                    .with_availability(Availability::None)
+                    .with_accept(AcceptLogic::None)
+                    .with_empty_accept(EmptyAcceptLogic::None)
+                    .with_pile_style(BoardItemStyle::Empty)
+            });
+
+            self.foundation_ids.push(id);
+        }
+        board.make_interchangeable(&self.foundation_ids);
+
+        // Tableau
+        for i in 0..10 {
+            let loc = BoardLocation { x: i, y: 1 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_availability(Availability::Ordered)
+                    .with_suit_ordering(SuitOrdering::Any)
+                    .with_run_suit_ordering(SuitOrdering::Same)
+                    .with_rank_ordering(RankOrdering::Decrementing)
+                    .with_empty_accept(EmptyAcceptLogic::Any)
+                    .with_pile_style(BoardItemStyle::Empty)
+                    .with_pile_flow(PileFlow::Down)
+                    .with_accept(AcceptLogic::Ordered)
+            });
+
+            self.tableau_ids.push(id);
+        }
+
+        let mut deck = Deck::suited_decks(2, &self.suits);
+        deck.shuffle(seed);
+
+        // 54 cards round the tableau leave the first four piles one card taller, with only the top cards face up
+        for i in 0..54 {
+            let card = deck.deal_card().expect("Two decks cover the tableau");
+            board.get_pile_mut(self.tableau_ids[i % 10]).add_card(card.turned_over());
+        }
+        board.auto_flip();
+
+        while let Some(card) = deck.deal_card() {
+            board.get_pile_mut(self.stock_id).add_card(card.turned_over());
+        }
+    }
+
+    fn foundation_ids(&self) -> &[PileId] {
+        &self.foundation_ids
+    }
+
+    fn tableau_ids(&self) -> &[PileId] {
+        &self.tableau_ids
+    }
+
+    fn get_status(&self, board: &Board) -> GameStatus {
+        if self.foundation_ids.iter().all(|id| !board.get_pile(*id).is_empty()) {
+            return GameStatus::Won;
+        }
+
+        match self.legal_moves(board).is_empty() && self.on_action(board, self.stock_action).is_empty() {
+            true => GameStatus::Lost,
+            false => GameStatus::Ongoing,
+        }
+    }
+
+    fn on_action(&self, board: &Board, action: ActionItemId) -> Vec<MoveStep> {
+        if action != self.stock_action { return Vec::new() }
+
+        // Dealing isn't allowed while any tableau pile is empty
+        let stock_empty = board.get_pile(self.stock_id).is_empty();
+        if stock_empty || self.tableau_ids.iter().any(|id| board.get_pile(*id).is_empty()) {
+            return Vec::new();
+        }
+
+        self.tableau_ids.iter()
+            .map(|id| MoveStep::Transfer { source: self.stock_id, target: *id, count: 1 })
+            .collect()
+    }
+
+    fn auto_move(&self, board: &Board) -> Option<MoveStep> {
+        let source = *self.tableau_ids.iter().find(|id| self.has_completed_run(board, **id))?;
+        let target = *self.foundation_ids.iter().find(|id| board.get_pile(**id).is_empty())?;
+
+        Some(MoveStep::Transfer { source, target, count: RUN_LENGTH })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::CardLocation;
+    use crate::cards::{Card, Rank};
+    use crate::games::{Game, GameEvent};
+
+    /// A one-suit game with the tableau cleared, leaving `arrange` to set the cards out.
+    fn cleared_spider(arrange: impl FnOnce(&Spider, &mut Board)) -> Game {
+        Game::arranged(Spider::one_suit(), 3, |logic, board| {
+            for id in logic.tableau_ids.iter() {
+                board.get_pile_mut(*id).take_from_card(0);
+            }
+
+            arrange(logic, board);
+        })
+    }
+
+    fn spade(value: u8) -> Card {
+        Card::new(Suit::Spades, Rank::from_value(value).unwrap())
+    }
+
+    #[test]
+    fn deal_layout() {
+        let game = Game::arranged(Spider::two_suits(), 3, |_, _| {});
+        let board = game.board_ref();
+
+        let lengths: Vec<usize> = game.logic().tableau_ids().iter().map(|id| board.get_pile(*id).len()).collect();
+        assert_eq!(lengths, vec![6, 6, 6, 6, 5, 5, 5, 5, 5, 5]);
+        for id in game.logic().tableau_ids().iter() {
+            assert_eq!(board.get_pile(*id).card_iter().filter(|c| c.is_face_up()).count(), 1);
+        }
+
+        // The stock is the first pile made
+        let stock = board.get_pile(PileId(0));
+        assert_eq!(stock.len(), 50);
+        assert!(stock.card_iter().all(|c| c.suit == Suit::Spades || c.suit == Suit::Hearts));
+    }
+
+    #[test]
+    fn mixed_suit_runs_build_but_do_not_move() {
+        let game = cleared_spider(|logic, board| {
+            board.get_pile_mut(logic.tableau_ids[0]).add_card(Card::new(Suit::Hearts, Rank::Value(7)));
+            board.get_pile_mut(logic.tableau_ids[0]).add_card(spade(6));
+            board.get_pile_mut(logic.tableau_ids[1]).add_card(spade(8));
+        });
+        let (first, second) = (game.logic().tableau_ids()[0], game.logic().tableau_ids()[1]);
+
+        let pile = game.board_ref().get_pile(first);
+        assert!(!pile.is_card_available(0));
+        assert!(pile.is_card_available(1));
+        assert!(game.board_ref().get_pile(second).can_accept_cards(&[Card::new(Suit::Hearts, Rank::Value(7))]));
+    }
+
+    #[test]
+    fn completed_run_is_cleared_and_undone() {
+        let mut game = cleared_spider(|logic, board| {
+            for value in (2..=13).rev() {
+                board.get_pile_mut(logic.tableau_ids[0]).add_card(spade(value));
+            }
+            board.get_pile_mut(logic.tableau_ids[1]).add_card(spade(1));
+        });
+        let (first, second) = (game.logic().tableau_ids()[0], game.logic().tableau_ids()[1]);
+        let foundation = game.logic().foundation_ids()[0];
+
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: second, card_idx: 0 }));
+        game.handle_event(GameEvent::DropEvent(Some(first)));
+
+        assert!(game.board_ref().get_pile(first).is_empty());
+        assert_eq!(game.board_ref().get_pile(foundation).len(), 13);
+        assert_eq!(game.score(), 130);
+        assert!(game.position_text().to_string().lines().any(|line| line.starts_with("Foundation: KS")));
+
+        assert!(game.undo());
+        assert!(game.board_ref().get_pile(foundation).is_empty());
+        assert_eq!(game.score(), 0);
+        assert_eq!(game.board_ref().get_pile(first).len(), 12);
+        assert_eq!(game.board_ref().get_pile(second).len(), 1);
+    }
+
+    #[test]
+    fn deal_needs_every_pile_filled() {
+        let game = cleared_spider(|_, _| {});
+        let action = game.board_ref().action_item_iter().next().unwrap().id();
+        assert!(game.logic().on_action(game.board_ref(), action).is_empty());
+
+        let filled = cleared_spider(|logic, board| {
+            for id in logic.tableau_ids.iter() {
+                board.get_pile_mut(*id).add_card(spade(5));
+            }
+        });
+        assert_eq!(filled.logic().on_action(filled.board_ref(), action).len(), 10);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::board::{ActionItemId, Board, BoardItemStyle, BoardLocation, PileFlow, PileId};
+use crate::board::pile_logic::{AcceptLogic, Availability, AutoFlip, EmptyAcceptLogic, RankOrdering, SuitOrdering};
+use crate::cards::Deck;
+use crate::games::{GameLogic, GameStatus};
+use crate::games::golf::chain_score;
+use crate::journal::{JournalEntry, MoveStep};
+
+/// Where each row of the peaks sits across the board, in half squares. The rows are half a square apart going
+/// down, and each card is covered by the cards half a square either side of it in the row below.
+fn row_positions() -> [Vec<usize>; 4] {
+    [
+        (0..3).map(|peak| 6 * peak + 3).collect(),
+        (0..6).map(|idx| 6 * (idx / 2) + 2 + 2 * (idx % 2)).collect(),
+        (0..9).map(|idx| 2 * idx + 1).collect(),
+        (0..10).map(|idx| 2 * idx).collect(),
+    ]
+}
+
+/// TriPeaks: 28 cards dealt as three overlapping peaks, the bottom row face up and the rest turning over as
+/// they're uncovered. Uncovered cards are played onto the waste when they're a rank higher or lower than its top
+/// card, with kings and aces next to each other unless wrapping is turned off. The stock deals one card at a time
+/// onto the waste, and only goes through once.
+pub struct TriPeaks {
+    wrap: bool,
+    stock_id: PileId,
+    waste_id: PileId,
+    /// The cards of the peaks from the top row down, the tips of the peaks first.
+    tableau_ids: Vec<PileId>,
+    stock_action: ActionItemId,
+}
+
+impl TriPeaks {
+    /// Whether kings and aces can be played onto each other. They can by default.
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+}
+
+impl Default for TriPeaks {
+    fn default() -> Self {
+        Self {
+            wrap: true,
+            stock_id: PileId(0),
+            waste_id: PileId(0),
+            tableau_ids: Vec::new(),
+            stock_action: ActionItemId(0),
+        }
+    }
+}
+
+impl GameLogic for TriPeaks {
+    fn setup(&mut self, board: &mut Board, seed: u64) {
+        self.tableau_ids.clear();
+
+        // Stock
+        let loc = BoardLocation { x: 0, y: 3 };
+        self.stock_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::None)
+                .with_accept(AcceptLogic::None)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_auto_flip(AutoFlip::Never)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+        self.stock_action = board.create_action_item(loc, BoardItemStyle::Empty);
+
+        // Waste
+        let rank_ordering = match self.wrap {
+            true => RankOrdering::AdjacentWrapping,
+            false => RankOrdering::Adjacent,
+        };
+        let loc = BoardLocation { x: 1, y: 3 };
+        self.waste_id = board.create_pile(loc, |builder| {
+            builder
+                .with_pile_flow(PileFlow::Stack)
+                .with_availability(Availability::None)
+                .with_suit_ordering(SuitOrdering::Any)
+                .with_rank_ordering(rank_ordering)
+                .with_accept(AcceptLogic::Ordered)
+                .with_empty_accept(EmptyAcceptLogic::None)
+                .with_pile_style(BoardItemStyle::Empty)
+        });
+
+        // The peaks, a pile for each card, with later rows drawn on top of earlier ones
+        let rows = row_positions();
+        let mut placed: Vec<(usize, usize, PileId)> = Vec::new();
+        for (row, positions) in rows.iter().enumerate() {
+            for half_x in positions {
+                let loc = BoardLocation { x: (half_x / 2) as u8, y: (row / 2) as u8 };
+                let id = board.create_pile(loc, |builder| {
+                    builder
+                        .with_pile_flow(PileFlow::Stack)
+                        .with_availability(Availability::Top)
+                        .with_accept(AcceptLogic::None)
+                        .with_empty_accept(EmptyAcceptLogic::None)
+                        .with_pile_style(BoardItemStyle::None)
+                        .with_offset((half_x % 2) as f32 * 0.5, (row % 2) as f32 * 0.5)
+                });
+
+                placed.push((row, *half_x, id));
+                self.tableau_ids.push(id);
+            }
+        }
+
+        for (row, half_x, id) in &placed {
+            let covering: Vec<PileId> = placed.iter()
+                .filter(|(below, other_x, _)| *below == row + 1 && other_x.abs_diff(*half_x) == 1)
+                .map(|(_, _, other)| *other)
+                .collect();
+            board.cover(*id, &covering);
+        }
+
+        let mut deck = Deck::single_deck();
+        deck.shuffle(seed);
+
+        for (row, _, id) in &placed {
+            let card = deck.deal_card().expect("A single deck covers the peaks");
+            let card = if *row == rows.len() - 1 { card } else { card.turned_over() };
+            board.get_pile_mut(*id).add_card(card);
+        }
+
+        // The waste starts with a card of its own to play onto
+        let card = deck.deal_card().expect("A single deck has a card for the waste");
+        board.get_pile_mut(self.waste_id).add_card(card);
+
+        while let Some(card) = deck.deal_card() {
+            board.get_pile_mut(self.stock_id).add_card(card.turned_over());
+        }
+    }
+
+    fn tableau_ids(&self) -> &[PileId] {
+        &self.tableau_ids
+    }
+
+    fn get_status(&self, board: &Board) -> GameStatus {
+        if self.tableau_ids.iter().all(|id| board.get_pile(*id).is_empty()) {
+            return GameStatus::Won;
+        }
+
+        match self.legal_moves(board).is_empty() && board.get_pile(self.stock_id).is_empty() {
+            true => GameStatus::Lost,
+            false => GameStatus::Ongoing,
+        }
+    }
+
+    fn on_action(&self, board: &Board, action: ActionItemId) -> Vec<MoveStep> {
+        if action != self.stock_action || board.get_pile(self.stock_id).is_empty() { return Vec::new() }
+
+        vec![MoveStep::Transfer { source: self.stock_id, target: self.waste_id, count: 1 }]
+    }
+
+    /// The chain score Golf uses, plus 15 for clearing each of the first two peaks and 30 for the last.
+    fn score(&self, _board: &Board, history: &[JournalEntry]) -> i32 {
+        let peaks = &self.tableau_ids[..3];
+        let cleared = history.iter()
+            .filter(|entry| matches!(entry.steps.first(), Some(MoveStep::Transfer { source, .. }) if peaks.contains(source)))
+            .count();
+        let bonus = match cleared {
+            3 => 60,
+            cleared => cleared as i32 * 15,
+        };
+
+        chain_score(history, self.stock_id, self.waste_id) + bonus
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::CardLocation;
+    use crate::games::{Game, GameEvent};
+
+    // Pile ids are handed out in creation order: stock, waste, then the peaks from the top row down
+    const WASTE: PileId = PileId(1);
+
+    fn peak_card(idx: usize) -> PileId {
+        PileId(2 + idx)
+    }
+
+    /// A dealt game with some of its lines swapped for others, by line number.
+    fn tripeaks_game(logic: TriPeaks, lines: &[(usize, &str)]) -> Game {
+        let mut game = Game::create_with_logic(Box::new(logic));
+        game.setup_with_seed(5);
+
+        let mut position: Vec<String> = game.position_text().to_string().lines().map(String::from).collect();
+        for (line, text) in lines {
+            position[*line] = text.to_string();
+        }
+        game.set_position(&position.join("\n")).unwrap();
+        game
+    }
+
+    fn play(game: &mut Game, pile_id: PileId) {
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id, card_idx: 0 }));
+        game.handle_event(GameEvent::DropEvent(Some(WASTE)));
+    }
+
+    #[test]
+    fn covered_cards_turn_over_once_uncovered() {
+        // The first two cards of the bottom row cover the first card of the row above
+        let mut game = tripeaks_game(TriPeaks::default(), &[(1, "Pile: 5H"), (20, ": 4D"), (21, ": 5S")]);
+        let covered = CardLocation { pile_id: peak_card(9), card_idx: 0 };
+        assert!(!game.board_ref().get_pile(covered.pile_id).top_card().unwrap().is_face_up());
+
+        play(&mut game, peak_card(18));
+        assert!(!game.board_ref().is_card_available(covered));
+
+        play(&mut game, peak_card(19));
+        assert!(game.board_ref().get_pile(covered.pile_id).top_card().unwrap().is_face_up());
+        assert!(game.board_ref().is_card_available(covered));
+        assert_eq!(game.score(), 1 + 2);
+
+        // Taking it back turns the card face down again
+        assert!(game.undo());
+        assert!(!game.board_ref().get_pile(covered.pile_id).top_card().unwrap().is_face_up());
+    }
+
+    #[test]
+    fn kings_and_aces_wrap_unless_turned_off() {
+        let lines = [(1, "Pile: KH"), (20, ": AS")];
+
+        let mut game = tripeaks_game(TriPeaks::default(), &lines);
+        play(&mut game, peak_card(18));
+        assert!(game.board_ref().get_pile(peak_card(18)).is_empty());
+
+        let mut game = tripeaks_game(TriPeaks::default().with_wrap(false), &lines);
+        play(&mut game, peak_card(18));
+        assert_eq!(game.board_ref().get_pile(peak_card(18)).len(), 1);
+    }
+
+    #[test]
+    fn clearing_a_peak_scores_a_bonus() {
+        // Everything under the first peak is already gone, leaving its tip uncovered
+        let mut lines = vec![(1, "Pile: 2H"), (2, ": AS")];
+        lines.extend([5, 6, 11, 12, 13, 20, 21, 22, 23].map(|line| (line, ":")));
+        let mut game = tripeaks_game(TriPeaks::default(), &lines);
+
+        play(&mut game, peak_card(0));
+        assert_eq!(game.score(), 1 + 15);
+    }
+}
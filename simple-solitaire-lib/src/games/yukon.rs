@@ -0,0 +1,196 @@
+use crate::board::{Board, BoardItemStyle, BoardLocation, PileFlow, PileId};
+use crate::board::pile_logic::{AcceptLogic, Availability, EmptyAcceptLogic, RankOrdering, SuitOrdering};
+use crate::cards::{Deck, Rank, Suit};
+use crate::games::{GameLogic, GameStatus};
+
+/// Yukon: the whole deck dealt to seven columns, with the face-down cards of each column under five face-up ones.
+/// Any face-up card can be moved along with everything on top of it, whether those are in order or not, as long
+/// as the card itself builds down on the pile it goes to. Russian Solitaire is the same, building in suit rather
+/// than in alternating colours.
+pub struct Yukon {
+    suit_ordering: SuitOrdering,
+    foundation_ids: Vec<PileId>,
+    tableau_ids: Vec<PileId>,
+}
+
+impl Yukon {
+    fn with_suit_ordering(suit_ordering: SuitOrdering) -> Self {
+        Self {
+            suit_ordering,
+            foundation_ids: Vec::new(),
+            tableau_ids: Vec::new(),
+        }
+    }
+
+    pub fn russian() -> Self {
+        Self::with_suit_ordering(SuitOrdering::Same)
+    }
+}
+
+impl Default for Yukon {
+    fn default() -> Self {
+        Self::with_suit_ordering(SuitOrdering::AlternatingColor)
+    }
+}
+
+impl GameLogic for Yukon {
+    fn setup(&mut self, board: &mut Board, seed: u64) {
+        self.foundation_ids.clear();
+        self.tableau_ids.clear();
+
+        // Foundations
+        for i in 0..4 {
+            let suit = Suit::get_ordered()[i as usize];
+            let loc = BoardLocation { x: i + 3, y: 0 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_empty_accept(EmptyAcceptLogic::Only(Rank::Ace))
+                    .with_suit_ordering(SuitOrdering::Same)
+                    .with_rank_ordering(RankOrdering::Incrementing)
+                    .with_accept(AcceptLogic::Ordered)
+                    .with_pile_style(BoardItemStyle::Ace(suit))
+                    .with_pile_flow(PileFlow::Stack)
+                    .with_availability(Availability::Top)
+            });
+
+            self.foundation_ids.push(id);
+        }
+        board.make_interchangeable(&self.foundation_ids);
+
+        // Tableau
+        let suit_ordering = self.suit_ordering;
+        for i in 0..7 {
+            let loc = BoardLocation { x: i, y: 1 };
+            let id = board.create_pile(loc, |builder| {
+                builder
+                    .with_availability(Availability::All)
+                    .with_suit_ordering(suit_ordering)
+                    .with_rank_ordering(RankOrdering::Decrementing)
+                    .with_empty_accept(EmptyAcceptLogic::Only(Rank::King))
+                    .with_pile_style(BoardItemStyle::Empty)
+                    .with_pile_flow(PileFlow::Down)
+                    .with_accept(AcceptLogic::Ordered)
+            });
+
+            self.tableau_ids.push(id);
+        }
+
+        let mut deck = Deck::single_deck();
+        deck.shuffle(seed);
+
+        // The first column gets a single card, and each one after has one more face-down card under five face-up
+        for (column, id) in self.tableau_ids.iter().enumerate() {
+            let face_up = if column == 0 { 1 } else { 5 };
+            for i in 0..column + face_up {
+                let card = deck.deal_card().expect("A single deck covers the tableau");
+                let card = if i < column { card.turned_over() } else { card };
+                board.get_pile_mut(*id).add_card(card);
+            }
+        }
+    }
+
+    fn foundation_ids(&self) -> &[PileId] {
+        &self.foundation_ids
+    }
+
+    fn tableau_ids(&self) -> &[PileId] {
+        &self.tableau_ids
+    }
+
+    fn get_status(&self, board: &Board) -> GameStatus {
+        let foundations_complete = self.foundation_ids.iter().all(|id| {
+            board.get_pile(*id).top_card().is_some_and(|card| card.rank == Rank::King)
+        });
+
+        match (foundations_complete, self.legal_moves(board).is_empty()) {
+            (true, _) => GameStatus::Won,
+            (false, true) => GameStatus::Lost,
+            (false, false) => GameStatus::Ongoing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::CardLocation;
+    use crate::games::{Game, GameEvent};
+
+    // Pile ids are handed out in creation order: the foundations, then the columns
+    const COLUMNS: [PileId; 7] = [PileId(4), PileId(5), PileId(6), PileId(7), PileId(8), PileId(9), PileId(10)];
+
+    const POSITION: &str = "
+        Foundation:
+        Foundation:
+        Foundation:
+        Foundation:
+        : [2C] 9H 4S KD
+        : TC
+        : TH
+        : 3D
+        :
+        :
+        :
+    ";
+
+    fn yukon_game(logic: Yukon) -> Game {
+        let mut game = Game::create_with_logic(Box::new(logic));
+        game.setup_with_seed(1);
+        game.set_position(POSITION).unwrap();
+        game
+    }
+
+    fn move_cards(game: &mut Game, source: CardLocation, target: PileId) {
+        game.handle_event(GameEvent::SelectEvent(source));
+        game.handle_event(GameEvent::DropEvent(Some(target)));
+    }
+
+    #[test]
+    fn deal_layout() {
+        let mut game = Game::create_with_logic(Box::new(Yukon::default()));
+        game.setup_with_seed(1);
+
+        for (column, id) in COLUMNS.iter().enumerate() {
+            let pile = game.board_ref().get_pile(*id);
+            let face_up = if column == 0 { 1 } else { 5 };
+            assert_eq!(pile.len(), column + face_up);
+            assert_eq!(pile.card_iter().filter(|card| card.is_face_up()).count(), face_up);
+        }
+    }
+
+    #[test]
+    fn unordered_groups_move_together() {
+        let mut game = yukon_game(Yukon::default());
+        let nine = CardLocation { pile_id: COLUMNS[0], card_idx: 1 };
+        assert!(!game.board_ref().is_card_available(CardLocation { pile_id: COLUMNS[0], card_idx: 0 }));
+
+        // The nine takes the four and king on top of it along, though they're in no order
+        move_cards(&mut game, nine, COLUMNS[1]);
+        let target = game.board_ref().get_pile(COLUMNS[1]);
+        assert_eq!(target.card_iter().map(|card| card.to_string()).collect::<Vec<_>>(), ["TC", "9H", "4S", "KD"]);
+
+        // The face-down card left behind turns over
+        let source = game.board_ref().get_pile(COLUMNS[0]);
+        assert_eq!(source.len(), 1);
+        assert!(source.top_card().unwrap().is_face_up());
+    }
+
+    #[test]
+    fn russian_solitaire_builds_in_suit() {
+        let nine = CardLocation { pile_id: COLUMNS[0], card_idx: 1 };
+
+        let mut game = yukon_game(Yukon::russian());
+        move_cards(&mut game, nine, COLUMNS[1]);
+        assert_eq!(game.board_ref().get_pile(COLUMNS[1]).len(), 1);
+
+        move_cards(&mut game, nine, COLUMNS[2]);
+        assert_eq!(game.board_ref().get_pile(COLUMNS[2]).len(), 4);
+
+        // A group whose bottom card doesn't fit stays put, whatever is on top of it
+        let king = CardLocation { pile_id: COLUMNS[2], card_idx: 3 };
+        move_cards(&mut game, king, COLUMNS[3]);
+        assert_eq!(game.board_ref().get_pile(COLUMNS[2]).len(), 4);
+        move_cards(&mut game, king, COLUMNS[4]);
+        assert_eq!(game.board_ref().get_pile(COLUMNS[4]).len(), 1);
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use crate::board::PileId;
+
+/// A single reversible change to the board.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum MoveStep {
+    /// The top `count` cards of `source` were moved, in order, onto `target`.
+    Transfer { source: PileId, target: PileId, count: usize },
+    /// The top card of the pile was turned over.
+    Flip { pile_id: PileId },
+    /// The waste was turned back into the stock, using up one redeal.
+    Redeal,
+}
+
+/// Everything one player action did to the board. The first step is the move the player made,
+/// any following steps are what the game did in response to it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub steps: Vec<MoveStep>,
+}
+
+impl JournalEntry {
+    pub(crate) fn new(step: MoveStep) -> Self {
+        Self { steps: vec![step] }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MoveJournal {
+    done: Vec<JournalEntry>,
+    undone: Vec<JournalEntry>,
+}
+
+impl MoveJournal {
+    /// A journal with the entries already done, as when a saved game is loaded.
+    pub(crate) fn with_entries(done: Vec<JournalEntry>) -> Self {
+        Self { done, undone: Vec::new() }
+    }
+
+    /// Records a new entry. Anything that was undone can no longer be redone.
+    pub(crate) fn record(&mut self, entry: JournalEntry) {
+        self.done.push(entry);
+        self.undone.clear();
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    pub(crate) fn undo(&mut self) -> Option<&JournalEntry> {
+        let entry = self.done.pop()?;
+        self.undone.push(entry);
+        self.undone.last()
+    }
+
+    pub(crate) fn redo(&mut self) -> Option<&JournalEntry> {
+        let entry = self.undone.pop()?;
+        self.done.push(entry);
+        self.done.last()
+    }
+
+    pub(crate) fn entries(&self) -> &[JournalEntry] {
+        &self.done
+    }
+}
@@ -1,26 +1,56 @@
-use crate::cards::Suit::Clubs;
-use crate::games::{FreeCell, Game};
+use crate::games::{Canfield, DealMode, FreeCell, Game, Golf, Klondike, Pyramid, Spider, TriPeaks, Yukon};
 
 mod cards;
 mod board;
 mod games;
+mod journal;
+mod solver;
+mod save;
 
 pub struct GameEntry {
     pub name: &'static str,
     pub creator: fn() -> Game,
 }
 
+impl GameEntry {
+    /// Creates the game and deals it the way the mode asks. A `DealMode::Winnable` deal falls back on the last
+    /// seed it tried when none could be proven in time, so check `Game::is_proven_winnable` where it matters.
+    pub fn create(&self, mode: DealMode) -> Game {
+        let mut game = (self.creator)();
+        game.setup_with_mode(mode);
+        game
+    }
+}
+
 macro_rules! game_entry {
     ($game:ident) => {
         GameEntry {
             name: stringify!($game),
-            creator: || { Game::create_with_logic(Box::new($game::default()))}
+            creator: || { Game::create_with_logic(Box::new($game::default())).with_variant(stringify!($game)) }
+        }
+    };
+    ($name:expr, $logic:expr) => {
+        GameEntry {
+            name: $name,
+            creator: || { Game::create_with_logic(Box::new($logic)).with_variant($name) }
         }
     };
 }
 
-const GAME_ENTRIES: [GameEntry; 1]  = [
+const GAME_ENTRIES: [GameEntry; 13]  = [
     game_entry!(FreeCell),
+    game_entry!(FreeCell::MICROSOFT_VARIANT, FreeCell::microsoft()),
+    game_entry!(Klondike),
+    game_entry!("Klondike (Draw 3)", Klondike::draw_three()),
+    game_entry!(Spider),
+    game_entry!("Spider (2 Suits)", Spider::two_suits()),
+    game_entry!("Spider (4 Suits)", Spider::four_suits()),
+    game_entry!(Pyramid),
+    game_entry!(Golf),
+    game_entry!(TriPeaks),
+    game_entry!(Canfield),
+    game_entry!(Yukon),
+    game_entry!("Russian Solitaire", Yukon::russian()),
 ];
 
 pub fn get_game_entries() -> &'static [GameEntry] {
@@ -34,28 +64,75 @@ pub fn add(left: usize, right: usize) -> usize {
 pub mod prelude {
     pub mod cards {
         pub use crate::cards::Card;
+        pub use crate::cards::CardColor;
+        pub use crate::cards::Facing;
         pub use crate::cards::Suit;
         pub use crate::cards::Rank;
+        pub use crate::cards::ParseCardError;
         pub use crate::board::CardLocation;
+        pub use crate::board::Move;
+        pub use crate::board::PileId;
         pub use crate::board::BoardLocation;
+        pub use crate::board::Board;
         pub use crate::board::Pile;
         pub use crate::board::PileFlow;
         pub use crate::board::BoardItemStyle;
+        pub use crate::board::PileOffset;
+        pub use crate::board::ActionItem;
+        pub use crate::board::ActionItemId;
     }
     pub mod games {
         pub use crate::GameEntry;
         pub use crate::get_game_entries;
-        pub use crate::games::{Game, GameEvent, FreeCell};
+        pub use crate::games::{Game, GameEvent, GameStatus, StuckReason, AutoPlay, DealMode, Canfield, FreeCell, Golf, Klondike, Pyramid, Spider, TriPeaks, Yukon};
+        pub use crate::games::{PositionText, ParsePositionError};
+        pub use crate::journal::{JournalEntry, MoveStep};
+    }
+    pub mod save {
+        pub use crate::save::{load_game, save_game, LoadError, SAVE_VERSION};
+    }
+    pub mod solver {
+        pub use crate::solver::{FreeCellSolver, GameSolver, SolutionStep, SolveResult, SolverBudget};
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::games::GameStatus;
+    use crate::solver::{GameSolver, SolveResult, SolverBudget};
 
     #[test]
     fn it_works() {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn winnable_deals_can_be_checked_from_their_seed() {
+        let entry = get_game_entries().iter().find(|entry| entry.name == "Klondike").unwrap();
+        let budget = SolverBudget { max_nodes: 20_000, ..Default::default() };
+        let game = entry.create(DealMode::Winnable { budget, attempts: 50 });
+        assert!(game.is_proven_winnable());
+
+        let mut replayed = (entry.creator)();
+        replayed.setup_with_seed(game.seed());
+        let SolveResult::Solved(steps) = GameSolver::new(budget).with_peek().solve(&replayed) else {
+            panic!("The recorded seed deals the same winnable game");
+        };
+        for step in steps {
+            step.play(&mut replayed);
+        }
+        assert_eq!(replayed.get_status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn winnable_deals_are_dealt_without_attempts() {
+        let entry = get_game_entries().iter().find(|entry| entry.name == "Klondike").unwrap();
+        let game = entry.create(DealMode::Winnable { budget: SolverBudget::default(), attempts: 0 });
+
+        assert_eq!(game.board_ref().pile_iter().map(|pile| pile.len()).sum::<usize>(), 52);
+        assert_eq!(game.get_status(), GameStatus::Ongoing);
+        assert!(!game.is_proven_winnable());
+    }
 }
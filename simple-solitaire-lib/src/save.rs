@@ -0,0 +1,235 @@
+use std::fmt;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::cards::Card;
+use crate::games::Game;
+use crate::get_game_entries;
+use crate::journal::JournalEntry;
+
+/// The version of the save format that gets written. Saves from later versions aren't loaded.
+pub const SAVE_VERSION: u32 = 1;
+
+/// Everything needed to carry on with a game, as it's written to a save.
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    version: u32,
+    /// The name of the `GameEntry` the game was created from.
+    variant: String,
+    seed: u64,
+    /// Every pile's cards from the bottom up, with the piles in the order the game creates them.
+    piles: Vec<Vec<Card>>,
+    history: Vec<JournalEntry>,
+    /// Written for anyone reading the save. Loading works it out again from the cards and history.
+    score: i32,
+    elapsed_millis: u64,
+    /// Whether the solver proved the deal winnable when it was dealt. Saves written before this was kept load as
+    /// unproven.
+    #[serde(default)]
+    proven_winnable: bool,
+}
+
+/// Just enough of a save to tell which version wrote it, before reading the rest.
+#[derive(Deserialize)]
+struct SaveVersion {
+    version: u32,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    /// The save isn't JSON of the right shape.
+    Malformed(serde_json::Error),
+    UnsupportedVersion(u32),
+    UnknownVariant(String),
+    /// The cards or moves in the save don't fit the variant's layout, or don't lead back to the deal of its seed.
+    Mismatch,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Malformed(error) => write!(f, "malformed save: {error}"),
+            LoadError::UnsupportedVersion(version) => write!(f, "save version {version} is newer than {SAVE_VERSION}"),
+            LoadError::UnknownVariant(variant) => write!(f, "unknown game variant \"{variant}\""),
+            LoadError::Mismatch => write!(f, "the saved cards don't fit the game's layout"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(error: serde_json::Error) -> Self {
+        LoadError::Malformed(error)
+    }
+}
+
+/// Writes the whole game out as JSON. Cards the player is holding are saved back where they came from.
+pub fn save_game(game: &Game) -> String {
+    let saved = SavedGame {
+        version: SAVE_VERSION,
+        variant: game.variant().to_string(),
+        seed: game.seed(),
+        piles: game.settled_piles(),
+        history: game.history().to_vec(),
+        score: game.score(),
+        elapsed_millis: game.elapsed().as_millis() as u64,
+        proven_winnable: game.is_proven_winnable(),
+    };
+
+    serde_json::to_string_pretty(&saved).expect("Saved games only hold plain data")
+}
+
+/// Reads a game written by `save_game`, ready to carry on with, undo history included.
+pub fn load_game(save: &str) -> Result<Game, LoadError> {
+    let SaveVersion { version } = serde_json::from_str(save)?;
+    if version > SAVE_VERSION {
+        return Err(LoadError::UnsupportedVersion(version));
+    }
+
+    let saved: SavedGame = serde_json::from_str(save)?;
+    let entry = get_game_entries().iter()
+        .find(|entry| entry.name == saved.variant)
+        .ok_or(LoadError::UnknownVariant(saved.variant))?;
+
+    let mut game = (entry.creator)();
+    let elapsed = Duration::from_millis(saved.elapsed_millis);
+    match game.restore(saved.seed, saved.piles, saved.history, elapsed, saved.proven_winnable) {
+        true => Ok(game),
+        false => Err(LoadError::Mismatch),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{ActionItemId, CardLocation};
+    use crate::cards::{Facing, Rank};
+    use crate::games::{FreeCell, GameEvent};
+
+    fn klondike_in_progress() -> Game {
+        let entry = get_game_entries().iter().find(|entry| entry.name == "Klondike (Draw 3)").unwrap();
+        let mut game = (entry.creator)();
+        game.setup_with_seed(7);
+
+        for _ in 0..10 {
+            game.handle_event(GameEvent::ActionEvent(ActionItemId(0)));
+        }
+        if let Some(mv) = game.hint() {
+            game.handle_event(GameEvent::SelectEvent(mv.source));
+            game.handle_event(GameEvent::DropEvent(Some(mv.target)));
+        }
+        game.add_elapsed(Duration::from_secs(95));
+
+        game
+    }
+
+    #[test]
+    fn saved_game_loads_as_it_was() {
+        let mut game = klondike_in_progress();
+        let mut loaded = load_game(&save_game(&game)).expect("The save was just written");
+
+        assert_eq!(loaded.variant(), "Klondike (Draw 3)");
+        assert_eq!(loaded.seed(), 7);
+        assert_eq!(loaded.board_ref(), game.board_ref());
+        assert_eq!(loaded.board_ref().redeal_count(), game.board_ref().redeal_count());
+        assert_eq!(loaded.history().len(), game.history().len());
+        assert_eq!(loaded.score(), game.score());
+        assert_eq!(loaded.elapsed(), Duration::from_secs(95));
+
+        // The history comes along, so the loaded game undoes just like the original
+        while game.undo() {
+            assert!(loaded.undo());
+            assert_eq!(loaded.board_ref(), game.board_ref());
+        }
+        assert!(!loaded.can_undo());
+    }
+
+    #[test]
+    fn saves_that_do_not_fit_are_refused() {
+        let save = save_game(&klondike_in_progress());
+
+        let newer = save.replacen(&format!("\"version\": {SAVE_VERSION}"), "\"version\": 99", 1);
+        assert!(matches!(load_game(&newer), Err(LoadError::UnsupportedVersion(99))));
+
+        let renamed = save.replacen("Klondike (Draw 3)", "Patience", 1);
+        assert!(matches!(load_game(&renamed), Err(LoadError::UnknownVariant(_))));
+
+        let mut saved: SavedGame = serde_json::from_str(&save).unwrap();
+        saved.piles.pop();
+        assert!(matches!(load_game(&serde_json::to_string(&saved).unwrap()), Err(LoadError::Mismatch)));
+
+        assert!(matches!(load_game("{}"), Err(LoadError::Malformed(_))));
+    }
+
+    #[test]
+    fn saves_with_the_wrong_cards_are_refused() {
+        let saved: SavedGame = serde_json::from_str(&save_game(&klondike_in_progress())).unwrap();
+        let load_changed = |change: &dyn Fn(&mut SavedGame)| {
+            let mut changed: SavedGame = serde_json::from_str(&serde_json::to_string(&saved).unwrap()).unwrap();
+            change(&mut changed);
+            load_game(&serde_json::to_string(&changed).unwrap())
+        };
+
+        assert!(matches!(load_changed(&|saved| saved.piles[6][0].rank = Rank::Value(0)), Err(LoadError::Mismatch)));
+        assert!(matches!(load_changed(&|saved| saved.piles[6][0].rank = Rank::Value(200)), Err(LoadError::Mismatch)));
+        assert!(matches!(load_changed(&|saved| saved.piles[6][0] = saved.piles[7][0].clone()), Err(LoadError::Mismatch)));
+        assert!(matches!(load_changed(&|saved| { saved.piles[0].pop(); }), Err(LoadError::Mismatch)));
+
+        // The right cards still have to be where the seed and the history put them
+        assert!(matches!(load_changed(&|saved| saved.seed += 1), Err(LoadError::Mismatch)));
+        assert!(matches!(load_changed(&|saved| saved.piles.swap(6, 7)), Err(LoadError::Mismatch)));
+        let turn_up = |saved: &mut SavedGame| {
+            for card in saved.piles.iter_mut().flatten() {
+                card.facing = Facing::Up;
+            }
+        };
+        assert!(matches!(load_changed(&turn_up), Err(LoadError::Mismatch)));
+    }
+
+    #[test]
+    fn microsoft_deals_load_as_microsoft_deals() {
+        let mut game = FreeCell::microsoft_deal(617);
+        let column = game.board_ref().pile_iter().last().unwrap();
+        let cell = game.board_ref().pile_iter().next().unwrap().id();
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: column.id(), card_idx: column.len() - 1 }));
+        game.handle_event(GameEvent::DropEvent(Some(cell)));
+
+        let mut loaded = load_game(&save_game(&game)).expect("The save was just written");
+        assert_eq!(loaded.variant(), FreeCell::MICROSOFT_VARIANT);
+        assert_eq!(loaded.seed(), 617);
+        assert_eq!(loaded.board_ref(), game.board_ref());
+
+        // A new game from the loaded one is another Microsoft deal
+        assert!(loaded.undo());
+        assert_eq!(loaded.board_ref(), FreeCell::microsoft_deal(617).board_ref());
+        loaded.setup();
+        assert!((1..=FreeCell::MICROSOFT_DEAL_COUNT as u64).contains(&loaded.seed()));
+    }
+
+    #[test]
+    fn proven_winnable_deals_stay_proven() {
+        let mut saved: SavedGame = serde_json::from_str(&save_game(&klondike_in_progress())).unwrap();
+        assert!(!saved.proven_winnable);
+
+        saved.proven_winnable = true;
+        let loaded = load_game(&serde_json::to_string(&saved).unwrap()).unwrap();
+        assert!(loaded.is_proven_winnable());
+        assert!(serde_json::from_str::<SavedGame>(&save_game(&loaded)).unwrap().proven_winnable);
+
+        let older = save_game(&klondike_in_progress()).replacen("\"proven_winnable\": false", "\"older\": true", 1);
+        assert!(!older.contains("proven_winnable"));
+        assert!(!load_game(&older).unwrap().is_proven_winnable());
+    }
+
+    #[test]
+    fn freecell_saves_keep_to_their_seed() {
+        let entry = get_game_entries().iter().find(|entry| entry.name == "FreeCell").unwrap();
+        let mut game = (entry.creator)();
+        game.setup_with_seed(42);
+        let mut saved: SavedGame = serde_json::from_str(&save_game(&game)).unwrap();
+        assert!(load_game(&serde_json::to_string(&saved).unwrap()).is_ok());
+
+        saved.seed = 43;
+        assert!(matches!(load_game(&serde_json::to_string(&saved).unwrap()), Err(LoadError::Mismatch)));
+    }
+}
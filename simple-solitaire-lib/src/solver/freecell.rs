@@ -0,0 +1,502 @@
+use std::collections::{BinaryHeap, HashSet};
+use std::time::Instant;
+use crate::board::{Board, CardLocation, Move, PileId};
+use crate::cards::{Card, CardColor, Suit};
+use crate::games::Game;
+use crate::solver::{KeyHasher, OpenNode, SolutionStep, SolveResult, SolverBudget};
+
+const FOUNDATIONS: usize = 4;
+const CELLS: usize = 4;
+const COLUMNS: usize = 8;
+/// A column only ever holds the seven cards it was dealt plus a run down from a King on top of them. Positions
+/// that could outgrow it are refused up front.
+const COLUMN_CAPACITY: usize = 20;
+
+/// A card packed into a byte, the suit in the high bits and the rank in the low four. Zero means no card.
+type Code = u8;
+
+fn encode(card: &Card) -> Code {
+    (card.suit as u8) << 4 | card.get_rank_value()
+}
+
+fn rank(code: Code) -> u8 {
+    code & 0xf
+}
+
+fn suit(code: Code) -> u8 {
+    code >> 4
+}
+
+fn is_red(code: Code) -> bool {
+    Suit::get_ordered()[suit(code) as usize].get_color() == CardColor::Red
+}
+
+#[derive(Copy, Clone)]
+struct Column {
+    len: u8,
+    cards: [Code; COLUMN_CAPACITY],
+}
+
+impl Column {
+    fn cards(&self) -> &[Code] {
+        &self.cards[..self.len as usize]
+    }
+
+    fn top(&self) -> Code {
+        match self.len {
+            0 => 0,
+            len => self.cards[len as usize - 1],
+        }
+    }
+
+    /// Whether the column stays within its capacity however it's built on. Each card can have at most a run down
+    /// to an ace on top of it, so a card's place and rank together bound how long the column can get from there.
+    fn fits<'a>(cards: impl Iterator<Item = &'a Card>) -> bool {
+        cards.enumerate().all(|(idx, card)| idx + card.get_rank_value() as usize <= COLUMN_CAPACITY)
+    }
+
+    fn push(&mut self, code: Code) {
+        self.cards[self.len as usize] = code;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Code {
+        self.len -= 1;
+        self.cards[self.len as usize]
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Spot {
+    Foundation(usize),
+    Cell(usize),
+    Column(usize),
+}
+
+/// Moving the top card of one spot to another. The solver only ever moves single cards, as every longer move
+/// the game allows can be made up of them.
+#[derive(Copy, Clone)]
+struct Step {
+    from: Spot,
+    to: Spot,
+}
+
+/// Which board piles the solver's spots stand for.
+struct Layout {
+    foundations: Vec<PileId>,
+    cells: Vec<PileId>,
+    columns: Vec<PileId>,
+}
+
+impl Layout {
+    fn pile_id(&self, spot: Spot) -> PileId {
+        match spot {
+            Spot::Foundation(i) => self.foundations[i],
+            Spot::Cell(i) => self.cells[i],
+            Spot::Column(i) => self.columns[i],
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Position {
+    foundations: [Code; FOUNDATIONS],
+    cells: [Code; CELLS],
+    columns: [Column; COLUMNS],
+}
+
+impl Position {
+    fn from_board(board: &Board, layout: &Layout) -> Self {
+        let top = |id: &PileId| board.get_pile(*id).top_card().map(encode).unwrap_or(0);
+        let mut position = Position {
+            foundations: [0; FOUNDATIONS],
+            cells: [0; CELLS],
+            columns: [Column { len: 0, cards: [0; COLUMN_CAPACITY] }; COLUMNS],
+        };
+
+        for (slot, id) in position.foundations.iter_mut().zip(layout.foundations.iter()) {
+            *slot = top(id);
+        }
+        for (slot, id) in position.cells.iter_mut().zip(layout.cells.iter()) {
+            *slot = top(id);
+        }
+        for (column, id) in position.columns.iter_mut().zip(layout.columns.iter()) {
+            for card in board.get_pile(*id).card_iter() {
+                column.push(encode(card));
+            }
+        }
+
+        position
+    }
+
+    fn top(&self, spot: Spot) -> Code {
+        match spot {
+            Spot::Foundation(i) => self.foundations[i],
+            Spot::Cell(i) => self.cells[i],
+            Spot::Column(i) => self.columns[i].top(),
+        }
+    }
+
+    fn apply(&mut self, step: Step) {
+        let code = match step.from {
+            Spot::Foundation(_) => unreachable!("Cards never leave the foundations"),
+            Spot::Cell(i) => std::mem::take(&mut self.cells[i]),
+            Spot::Column(i) => self.columns[i].pop(),
+        };
+
+        match step.to {
+            Spot::Foundation(i) => self.foundations[i] = code,
+            Spot::Cell(i) => self.cells[i] = code,
+            Spot::Column(i) => self.columns[i].push(code),
+        }
+    }
+
+    fn to_move(&self, step: Step, layout: &Layout) -> Move {
+        let card_idx = match step.from {
+            Spot::Column(i) => self.columns[i].len as usize - 1,
+            _ => 0,
+        };
+
+        Move { source: CardLocation { pile_id: layout.pile_id(step.from), card_idx }, target: layout.pile_id(step.to) }
+    }
+
+    /// How many cards have been played off.
+    fn home(&self) -> usize {
+        self.foundations.iter().map(|code| rank(*code) as usize).sum()
+    }
+
+    fn card_count(&self) -> usize {
+        self.home() + self.cells.iter().filter(|code| **code != 0).count()
+            + self.columns.iter().map(|c| c.len as usize).sum::<usize>()
+    }
+
+    fn foundation_height(&self, suit_idx: u8) -> u8 {
+        self.foundations.iter().find(|code| **code != 0 && suit(**code) == suit_idx).map_or(0, |code| rank(*code))
+    }
+
+    /// The foundation the card would go to on the board, the first one it builds on.
+    fn foundation_for(&self, code: Code) -> Option<usize> {
+        self.foundations.iter().position(|top| match *top {
+            0 => rank(code) == 1,
+            top => suit(top) == suit(code) && rank(top) + 1 == rank(code),
+        })
+    }
+
+    /// Whether nothing could still need building on the card, so playing it off can't lose the game.
+    fn is_safe(&self, code: Code) -> bool {
+        (0..4).filter(|s| is_red(*s << 4 | 1) != is_red(code))
+            .all(|s| self.foundation_height(s) + 1 >= rank(code))
+    }
+
+    fn next_auto_play(&self) -> Option<Step> {
+        let cells = (0..CELLS).map(Spot::Cell);
+        let columns = (0..COLUMNS).map(Spot::Column);
+
+        cells.chain(columns).find_map(|from| {
+            let code = self.top(from);
+            if code == 0 || !self.is_safe(code) { return None }
+
+            self.foundation_for(code).map(|i| Step { from, to: Spot::Foundation(i) })
+        })
+    }
+
+    fn auto_play(&mut self) {
+        while let Some(step) = self.next_auto_play() {
+            self.apply(step);
+        }
+    }
+
+    fn steps(&self) -> Vec<Step> {
+        let mut steps = Vec::new();
+        let first_empty_cell = self.cells.iter().position(|code| *code == 0);
+        let first_empty_column = self.columns.iter().position(|c| c.len == 0);
+
+        let cells = (0..CELLS).map(Spot::Cell);
+        let columns = (0..COLUMNS).map(Spot::Column);
+        for from in cells.chain(columns) {
+            let code = self.top(from);
+            if code == 0 { continue }
+
+            if let Some(i) = self.foundation_for(code) {
+                steps.push(Step { from, to: Spot::Foundation(i) });
+            }
+
+            for (i, column) in self.columns.iter().enumerate() {
+                let to = Spot::Column(i);
+                let builds = match column.top() {
+                    0 => Some(i) == first_empty_column && !matches!(from, Spot::Column(j) if self.columns[j].len == 1),
+                    top => rank(top) == rank(code) + 1 && is_red(top) != is_red(code),
+                };
+
+                if builds && to != from {
+                    steps.push(Step { from, to });
+                }
+            }
+
+            if let (Spot::Column(_), Some(i)) = (from, first_empty_cell) {
+                steps.push(Step { from, to: Spot::Cell(i) });
+            }
+        }
+
+        steps
+    }
+
+    /// A rough count of the moves still needed: every card not yet home, plus every card sitting on top of a
+    /// lower one, plus every card taking up a cell.
+    fn estimate(&self) -> usize {
+        let blocking: usize = self.columns.iter().map(|column| {
+            let mut lowest = u8::MAX;
+            column.cards().iter().filter(|code| {
+                let blocks = rank(**code) > lowest;
+                lowest = lowest.min(rank(**code));
+                blocks
+            }).count()
+        }).sum();
+        let occupied = self.cells.iter().filter(|code| **code != 0).count();
+
+        (self.card_count() - self.home()) + blocking + occupied
+    }
+
+    /// Identifies the position regardless of which cell or column each card is in, as swapping cells or whole
+    /// columns around changes nothing. The key is a 128-bit hash, so two positions sharing one is vanishingly
+    /// unlikely rather than impossible.
+    fn key(&self) -> u128 {
+        let mut cells = self.cells;
+        cells.sort_unstable();
+        let mut columns: [&[Code]; COLUMNS] = std::array::from_fn(|i| self.columns[i].cards());
+        columns.sort_unstable();
+
+        let mut hasher = KeyHasher::default();
+        for suit_idx in 0..4 {
+            hasher.write(self.foundation_height(suit_idx));
+        }
+        for code in cells {
+            hasher.write(code);
+        }
+        for column in columns {
+            for code in column {
+                hasher.write(*code);
+            }
+            hasher.write(0xff);
+        }
+
+        hasher.finish()
+    }
+}
+
+/// Searches a FreeCell game for a win, best first, steering by how many moves are probably still needed.
+/// Positions already seen are skipped, so when the search runs dry every reachable position has been tried and
+/// the deal can't be won.
+pub struct FreeCellSolver {
+    budget: SolverBudget,
+}
+
+impl FreeCellSolver {
+    /// How much more the estimate of moves left counts for than the moves made so far. Anything above one trades
+    /// the shortest solution for finding one sooner.
+    const ESTIMATE_WEIGHT: usize = 2;
+
+    pub fn new(budget: SolverBudget) -> Self {
+        Self { budget }
+    }
+
+    /// Solves the game from where it stands, with any cards the player is holding back where they came from.
+    /// Playing the first step puts them back in the game too. Games without the standard FreeCell layout are
+    /// `Unsupported`, as are positions with columns too long for the solver.
+    pub fn solve(&self, game: &Game) -> SolveResult {
+        let logic = game.logic();
+        let layout = Layout {
+            foundations: logic.foundation_ids().to_vec(),
+            cells: logic.cell_ids().to_vec(),
+            columns: logic.tableau_ids().to_vec(),
+        };
+        let standard = layout.foundations.len() == FOUNDATIONS
+            && layout.cells.len() == CELLS
+            && layout.columns.len() == COLUMNS;
+        if !standard { return SolveResult::Unsupported }
+
+        let board = game.settled_board();
+        if !layout.columns.iter().all(|id| Column::fits(board.get_pile(*id).card_iter())) {
+            return SolveResult::Unsupported;
+        }
+
+        let origin = Position::from_board(&board, &layout);
+        let total = origin.card_count();
+
+        let mut start = origin.clone();
+        start.auto_play();
+
+        // The clock is only read when there's a time limit, since wasm32 has no clock to read
+        let deadline = self.budget.max_time.map(|limit| (Instant::now(), limit));
+        let mut seen = HashSet::from([start.key()]);
+        // Each node is the step that reached it and the node it was taken from
+        let mut nodes: Vec<(usize, Option<Step>)> = vec![(0, None)];
+        let mut open = BinaryHeap::from([OpenNode { score: start.estimate(), depth: 0, node: 0, position: start }]);
+        let mut expanded = 0;
+
+        while let Some(OpenNode { depth, node, position, .. }) = open.pop() {
+            if position.home() == total {
+                return SolveResult::Solved(Self::replay(origin, &nodes, node, &layout));
+            }
+
+            expanded += 1;
+            let out_of_time = deadline.is_some_and(|(started, limit)| expanded % 1024 == 0 && started.elapsed() > limit);
+            if expanded > self.budget.max_nodes || out_of_time {
+                return SolveResult::GaveUp;
+            }
+
+            for step in position.steps() {
+                let mut child = position.clone();
+                child.apply(step);
+                child.auto_play();
+
+                if !seen.insert(child.key()) { continue }
+
+                nodes.push((node, Some(step)));
+                open.push(OpenNode {
+                    score: depth + 1 + child.estimate() * Self::ESTIMATE_WEIGHT,
+                    depth: depth + 1,
+                    node: nodes.len() - 1,
+                    position: child,
+                });
+            }
+        }
+
+        SolveResult::Unsolvable
+    }
+
+    /// Turns the steps leading to `node` back into board moves, with the cards played off along the way.
+    fn replay(mut position: Position, nodes: &[(usize, Option<Step>)], mut node: usize, layout: &Layout) -> Vec<SolutionStep> {
+        let mut steps = Vec::new();
+        while let (parent, Some(step)) = nodes[node] {
+            steps.push(step);
+            node = parent;
+        }
+
+        let mut moves = Vec::new();
+        let mut take = |position: &mut Position, step: Step| {
+            moves.push(SolutionStep::Move(position.to_move(step, layout)));
+            position.apply(step);
+        };
+
+        while let Some(step) = position.next_auto_play() {
+            take(&mut position, step);
+        }
+        for step in steps.into_iter().rev() {
+            take(&mut position, step);
+            while let Some(step) = position.next_auto_play() {
+                take(&mut position, step);
+            }
+        }
+
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{AutoPlay, FreeCell, GameEvent, GameStatus, Klondike};
+
+    fn play(game: &mut Game, steps: &[SolutionStep]) {
+        for step in steps {
+            step.play(game);
+        }
+    }
+
+    #[test]
+    fn solution_wins_the_deal() {
+        let mut game = FreeCell::microsoft_deal(1);
+        let SolveResult::Solved(steps) = FreeCellSolver::new(SolverBudget::default()).solve(&game) else {
+            panic!("Deal 1 can be won");
+        };
+
+        play(&mut game, &steps);
+        assert_eq!(game.get_status(), GameStatus::Won);
+        assert_eq!(game.history().len(), steps.len());
+    }
+
+    #[test]
+    fn solves_from_the_middle_of_a_game() {
+        let mut game = FreeCell::microsoft_deal(617);
+        let column = game.board_ref().pile_iter().last().unwrap();
+        let top = CardLocation { pile_id: column.id(), card_idx: column.len() - 1 };
+        let cell = game.board_ref().pile_iter().next().unwrap().id();
+        play(&mut game, &[SolutionStep::Move(Move { source: top, target: cell })]);
+
+        let SolveResult::Solved(steps) = FreeCellSolver::new(SolverBudget::default()).solve(&game) else {
+            panic!("Deal 617 can still be won");
+        };
+
+        play(&mut game, &steps);
+        assert_eq!(game.get_status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn held_cards_are_solved_where_they_came_from() {
+        let mut game = FreeCell::microsoft_deal(1);
+        let solver = FreeCellSolver::new(SolverBudget::default());
+        let settled = solver.solve(&game);
+
+        let column = game.board_ref().pile_iter().last().unwrap();
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: column.id(), card_idx: column.len() - 1 }));
+        let SolveResult::Solved(steps) = solver.solve(&game) else {
+            panic!("Deal 1 can be won");
+        };
+        assert_eq!(SolveResult::Solved(steps.clone()), settled);
+
+        play(&mut game, &steps);
+        assert_eq!(game.get_status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn solutions_play_out_whatever_the_auto_play() {
+        for deal_number in [617, 2] {
+            for auto_play in [AutoPlay::None, AutoPlay::SafeOnly, AutoPlay::Always] {
+                let mut game = FreeCell::microsoft_deal(deal_number);
+                game.set_auto_play(auto_play);
+                let SolveResult::Solved(steps) = FreeCellSolver::new(SolverBudget::default()).solve(&game) else {
+                    panic!("Deal {deal_number} can be won");
+                };
+
+                play(&mut game, &steps);
+                assert_eq!(game.get_status(), GameStatus::Won, "deal {deal_number} with {auto_play:?}");
+                assert_eq!(game.auto_play(), auto_play);
+            }
+        }
+    }
+
+    #[test]
+    fn gives_up_when_the_budget_runs_out() {
+        let game = FreeCell::microsoft_deal(1);
+        let budget = SolverBudget { max_nodes: 10, ..Default::default() };
+
+        assert_eq!(FreeCellSolver::new(budget).solve(&game), SolveResult::GaveUp);
+    }
+
+    #[test]
+    fn other_games_are_unsupported() {
+        let mut game = Game::create_with_logic(Box::new(Klondike::default()));
+        game.setup_with_seed(1);
+
+        assert_eq!(FreeCellSolver::new(SolverBudget::default()).solve(&game), SolveResult::Unsupported);
+    }
+
+    #[test]
+    fn overlong_columns_are_unsupported() {
+        let mut game = FreeCell::microsoft_deal(1);
+        let mut position = game.position_text().to_string();
+        position = position.replacen(": ", ": KS KH KD KC QS QH QD QC JS JH JD JC TS TH ", 1);
+        game.set_position(&position).unwrap();
+
+        assert_eq!(FreeCellSolver::new(SolverBudget::default()).solve(&game), SolveResult::Unsupported);
+    }
+
+    #[test]
+    fn deal_11982_is_unsolvable() {
+        let game = FreeCell::microsoft_deal(11982);
+        let budget = SolverBudget { max_nodes: usize::MAX, ..Default::default() };
+
+        assert_eq!(FreeCellSolver::new(budget).solve(&game), SolveResult::Unsolvable);
+    }
+}
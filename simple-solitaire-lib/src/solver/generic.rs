@@ -0,0 +1,215 @@
+use std::collections::{BinaryHeap, HashMap};
+use std::time::Instant;
+use crate::board::{Board, CardLocation, Move};
+use crate::games::{Game, GameLogic, GameStatus};
+use crate::journal::MoveStep;
+use crate::solver::{OpenNode, SolutionStep, SolveResult, SolverBudget};
+
+/// Searches any game for a win using nothing but the moves, actions and picks its rules allow, so every variant can be
+/// checked without a solver of its own. Slower than a solver that knows the game, like `FreeCellSolver`.
+///
+/// Face-down cards are hidden information, and by default the solver won't look at them. Peeking lets it see
+/// every card where it lies, which answers whether the deal can be won rather than whether a player could.
+pub struct GameSolver {
+    budget: SolverBudget,
+    peek: bool,
+}
+
+impl GameSolver {
+    /// How much more the estimate of moves left counts for than the moves made so far.
+    const ESTIMATE_WEIGHT: usize = 2;
+
+    pub fn new(budget: SolverBudget) -> Self {
+        Self { budget, peek: false }
+    }
+
+    /// Lets the solver see face-down cards, for analysing deals from the dealer's side.
+    pub fn with_peek(mut self) -> Self {
+        self.peek = true;
+        self
+    }
+
+    /// Solves the game from where it stands, with any cards the player is holding back where they came from.
+    /// Playing the first step puts them back in the game too.
+    pub fn solve(&self, game: &Game) -> SolveResult {
+        let logic = game.logic();
+        let start = game.settled_board();
+
+        let hidden = start.pile_iter().any(|pile| pile.card_iter().any(|card| !card.is_face_up()));
+        if hidden && !self.peek {
+            return SolveResult::NeedsPeek;
+        }
+
+        // The clock is only read when there's a time limit, since wasm32 has no clock to read
+        let deadline = self.budget.max_time.map(|limit| (Instant::now(), limit));
+        // Reaching a position again is only worth anything if it has more redeals left than before
+        let mut seen = HashMap::from([(start.position_hash(), start.redeal_count())]);
+        // Each node is the step that reached it and the node it was taken from
+        let mut nodes: Vec<(usize, Option<SolutionStep>)> = vec![(0, None)];
+        let mut open = BinaryHeap::from([OpenNode { score: estimate(logic, &start), depth: 0, node: 0, position: start }]);
+        let mut expanded = 0;
+
+        while let Some(OpenNode { depth, node, position, .. }) = open.pop() {
+            if logic.get_status(&position) == GameStatus::Won {
+                return SolveResult::Solved(solution(&nodes, node));
+            }
+
+            expanded += 1;
+            let out_of_time = deadline.is_some_and(|(started, limit)| expanded % 1024 == 0 && started.elapsed() > limit);
+            if expanded > self.budget.max_nodes || out_of_time {
+                return SolveResult::GaveUp;
+            }
+
+            for (step, steps) in choices(logic, &position) {
+                let mut child = position.clone();
+                play(logic, &mut child, &steps);
+
+                let redeals = seen.entry(child.position_hash()).or_insert(usize::MAX);
+                if *redeals <= child.redeal_count() { continue }
+                *redeals = child.redeal_count();
+
+                nodes.push((node, Some(step)));
+                open.push(OpenNode {
+                    score: depth + 1 + estimate(logic, &child) * Self::ESTIMATE_WEIGHT,
+                    depth: depth + 1,
+                    node: nodes.len() - 1,
+                    position: child,
+                });
+            }
+        }
+
+        SolveResult::Unsolvable
+    }
+}
+
+/// Everything the player could do, with the board steps each one takes.
+fn choices(logic: &dyn GameLogic, board: &Board) -> Vec<(SolutionStep, Vec<MoveStep>)> {
+    let moves = logic.distinct_moves(board).into_iter().map(|mv: Move| {
+        let step = MoveStep::Transfer { source: mv.source.pile_id, target: mv.target, count: mv.card_count(board) };
+        (SolutionStep::Move(mv), vec![step])
+    });
+    let actions = board.action_item_iter()
+        .map(|item| (SolutionStep::Action(item.id()), logic.on_action(board, item.id())))
+        .filter(|(_, steps)| !steps.is_empty());
+    let picks = logic.pick_choices(board).into_iter().map(|picked| {
+        let steps = logic.on_pick(board, &picked);
+        (SolutionStep::Pick(picked[0], picked.get(1).copied()), steps)
+    });
+
+    moves.chain(actions).chain(picks).collect()
+}
+
+/// Plays out one player action the way `Game` would, cards flipping and the game's own moves included.
+fn play(logic: &dyn GameLogic, board: &mut Board, steps: &[MoveStep]) {
+    for step in steps {
+        board.apply_step(step);
+        board.auto_flip();
+    }
+
+    while let Some(step) = logic.auto_move(board) {
+        board.apply_step(&step);
+        board.auto_flip();
+    }
+}
+
+/// A rough count of the moves still needed: every card not on a foundation, and again every card off the
+/// foundations that can't be picked up yet.
+fn estimate(logic: &dyn GameLogic, board: &Board) -> usize {
+    let foundations = logic.foundation_ids();
+
+    board.pile_iter()
+        .filter(|pile| !foundations.contains(&pile.id()))
+        .map(|pile| {
+            let blocked = (0..pile.len())
+                .filter(|idx| !board.is_card_available(CardLocation { pile_id: pile.id(), card_idx: *idx }))
+                .count();
+            pile.len() + blocked
+        })
+        .sum()
+}
+
+fn solution(nodes: &[(usize, Option<SolutionStep>)], mut node: usize) -> Vec<SolutionStep> {
+    let mut steps = Vec::new();
+    while let (parent, Some(step)) = nodes[node] {
+        steps.push(step);
+        node = parent;
+    }
+
+    steps.reverse();
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, Rank, Suit};
+    use crate::games::{FreeCell, GameEvent, Klondike};
+
+    fn play_solution(game: &mut Game, steps: &[SolutionStep]) {
+        for step in steps {
+            step.play(game);
+        }
+    }
+
+    #[test]
+    fn solves_freecell_through_the_rules_alone() {
+        let mut game = FreeCell::microsoft_deal(1);
+        let budget = SolverBudget { max_nodes: 200_000, ..Default::default() };
+        let SolveResult::Solved(steps) = GameSolver::new(budget).solve(&game) else {
+            panic!("Deal 1 can be won");
+        };
+
+        play_solution(&mut game, &steps);
+        assert_eq!(game.get_status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn held_cards_are_solved_where_they_came_from() {
+        let mut game = FreeCell::microsoft_deal(1);
+        let column = game.board_ref().pile_iter().last().unwrap();
+        game.handle_event(GameEvent::SelectEvent(CardLocation { pile_id: column.id(), card_idx: column.len() - 1 }));
+
+        let budget = SolverBudget { max_nodes: 200_000, ..Default::default() };
+        let SolveResult::Solved(steps) = GameSolver::new(budget).solve(&game) else {
+            panic!("Deal 1 can be won");
+        };
+
+        play_solution(&mut game, &steps);
+        assert_eq!(game.get_status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn hidden_cards_need_peeking() {
+        let mut game = Game::create_with_logic(Box::new(Klondike::draw_one()));
+        game.setup_with_seed(2);
+
+        assert_eq!(GameSolver::new(SolverBudget::default()).solve(&game), SolveResult::NeedsPeek);
+    }
+
+    #[test]
+    fn peeking_solves_klondike() {
+        let mut game = Game::create_with_logic(Box::new(Klondike::draw_one()));
+        game.setup_with_seed(10);
+
+        let budget = SolverBudget { max_nodes: 200_000, ..Default::default() };
+        let SolveResult::Solved(steps) = GameSolver::new(budget).with_peek().solve(&game) else {
+            panic!("Seed 10 can be won");
+        };
+
+        play_solution(&mut game, &steps);
+        assert_eq!(game.get_status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn exhausted_search_is_unsolvable() {
+        // With no ace to start from, the two can wander the board but never go home
+        let game = Game::arranged(FreeCell::default(), 1, |logic, board| {
+            for id in logic.tableau_ids().iter() {
+                board.get_pile_mut(*id).take_from_card(0);
+            }
+            board.get_pile_mut(logic.tableau_ids()[0]).add_card(Card::new(Suit::Spades, Rank::Value(2)));
+        });
+
+        assert_eq!(GameSolver::new(SolverBudget::default()).solve(&game), SolveResult::Unsolvable);
+    }
+}
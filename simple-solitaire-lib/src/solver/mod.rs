@@ -0,0 +1,124 @@
+use std::cmp::Ordering;
+use std::time::Duration;
+use crate::board::{ActionItemId, CardLocation, Move};
+use crate::games::{AutoPlay, Game, GameEvent};
+
+mod freecell;
+mod generic;
+
+pub use freecell::FreeCellSolver;
+pub use generic::GameSolver;
+
+/// How much searching a solver may do before giving up.
+#[derive(Copy, Clone, Debug)]
+pub struct SolverBudget {
+    /// The most positions the solver expands.
+    pub max_nodes: usize,
+    /// The longest the solver runs for, if limited.
+    pub max_time: Option<Duration>,
+}
+
+impl Default for SolverBudget {
+    fn default() -> Self {
+        Self { max_nodes: 1_000_000, max_time: None }
+    }
+}
+
+/// One thing the player does on the way to a win.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SolutionStep {
+    Move(Move),
+    Action(ActionItemId),
+    /// Picks out a card, and the one it pairs with if it doesn't go on its own.
+    Pick(CardLocation, Option<CardLocation>),
+}
+
+impl SolutionStep {
+    /// Plays the step on the game through the same events the player would send. Auto play is held off while it
+    /// does, as any cards a solution sends to the foundations are steps of their own.
+    pub fn play(&self, game: &mut Game) {
+        let auto_play = game.auto_play();
+        game.set_auto_play(AutoPlay::None);
+
+        match *self {
+            SolutionStep::Move(mv) => {
+                game.handle_event(GameEvent::SelectEvent(mv.source));
+                game.handle_event(GameEvent::DropEvent(Some(mv.target)));
+            }
+            SolutionStep::Action(action) => game.handle_event(GameEvent::ActionEvent(action)),
+            SolutionStep::Pick(first, second) => {
+                game.handle_event(GameEvent::PickEvent(first));
+                if let Some(second) = second {
+                    game.handle_event(GameEvent::PickEvent(second));
+                }
+            }
+        }
+
+        game.set_auto_play(auto_play);
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SolveResult {
+    /// The steps that win the game from the position given, in order.
+    Solved(Vec<SolutionStep>),
+    /// Every position reachable was searched and none of them win.
+    Unsolvable,
+    /// The budget ran out first.
+    GaveUp,
+    /// The position has face-down cards, and the solver wasn't allowed to peek at them.
+    NeedsPeek,
+    /// The game isn't one the solver knows how to play, such as a Klondike game given to `FreeCellSolver`.
+    Unsupported,
+}
+
+/// Two independent 64-bit FNV-style hashes side by side, for telling positions apart.
+struct KeyHasher {
+    low: u64,
+    high: u64,
+}
+
+impl Default for KeyHasher {
+    fn default() -> Self {
+        Self { low: 0xcbf2_9ce4_8422_2325, high: 0x6c62_272e_07bb_0142 }
+    }
+}
+
+impl KeyHasher {
+    fn write(&mut self, byte: u8) {
+        self.low = (self.low ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        self.high = (self.high ^ byte as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(29);
+    }
+
+    fn finish(&self) -> u128 {
+        (self.high as u128) << 64 | self.low as u128
+    }
+}
+
+/// A position waiting to be expanded, ordered so the heap hands out the most promising one first.
+struct OpenNode<P> {
+    score: usize,
+    depth: usize,
+    node: usize,
+    position: P,
+}
+
+impl<P> PartialEq for OpenNode<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P> Eq for OpenNode<P> {}
+
+impl<P> PartialOrd for OpenNode<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P> Ord for OpenNode<P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.score.cmp(&self.score).then(self.node.cmp(&other.node))
+    }
+}